@@ -1,47 +1,1013 @@
-use crate::storage::Storage;
-use crate::task::{Task, TaskStatus};
+use std::cmp::Reverse;
+use std::io::{self, Write};
+use crate::datetime::{self, Date};
+use crate::config::{ContextDef, Urgency};
+use crate::storage::{Storage, StorageError};
+use crate::state::State;
+use crate::task::{
+    children_of, depends_transitively, descendants_of, is_ready, open_blockers, project_summary,
+    resolve_id, tag_counts, tree_order, Priority, Recurrence, Task, TaskStatus, UdaValue,
+};
 use crate::quotes::Quotes;
 
+#[derive(Debug, Clone, Default)]
+pub struct TaskOptions {
+    pub due: Option<String>,
+    pub wait: Option<String>,
+    pub priority: Option<String>,
+    pub tags: Vec<String>,
+    pub project: Option<String>,
+    pub parent: Option<String>,
+    pub recur: Option<String>,
+    /// `name=value` assignments of user-defined attributes.
+    pub set: Vec<String>,
+}
+
+impl TaskOptions {
+    pub fn is_empty(&self) -> bool {
+        self.due.is_none() && self.wait.is_none() && self.priority.is_none() && self.tags.is_empty()
+            && self.project.is_none() && self.parent.is_none() && self.recur.is_none() && self.set.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ListOptions {
+    pub status: Option<String>,
+    pub show_age: bool,
+    pub overdue: bool,
+    pub due_before: Option<String>,
+    pub priority: Option<String>,
+    pub sort: Option<String>,
+    pub tags: Vec<String>,
+    pub project: Option<String>,
+    pub group_by_project: bool,
+    pub tree: bool,
+    pub ready: bool,
+    pub archived: bool,
+    /// Include tasks that are waiting for their scheduled date.
+    pub all: bool,
+    /// Only tasks assigned to the current user.
+    pub mine: bool,
+    pub assignee: Option<String>,
+    /// `name=value` conditions on user-defined attributes; `name=none`
+    /// matches tasks without the attribute.
+    pub wheres: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ResetOptions {
+    /// Skip the confirmation prompt.
+    pub yes: bool,
+    pub dry_run: bool,
+    pub status: Option<String>,
+    pub tags: Vec<String>,
+}
+
+impl ResetOptions {
+    pub fn is_scoped(&self) -> bool {
+        self.status.is_some() || !self.tags.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChildAction {
+    Remove,
+    Reparent,
+}
+
+impl ChildAction {
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<ChildAction> {
+        match s.to_lowercase().as_str() {
+            "remove" | "r" => Some(ChildAction::Remove),
+            "reparent" | "p" => Some(ChildAction::Reparent),
+            _ => None,
+        }
+    }
+}
+
+struct RowContext {
+    today: Date,
+    now: u64,
+    show_age: bool,
+}
+
 pub struct Commands {
     storage: Storage,
+    user: Option<String>,
+    urgency: Urgency,
+    contexts: Vec<ContextDef>,
+    state: Option<State>,
 }
 
-impl Commands {
-    pub fn new(storage: Storage) -> Commands {
-        Commands { storage }
+impl Commands {
+    pub fn new(storage: Storage) -> Commands {
+        Commands { storage, user: None, urgency: Urgency::default(), contexts: Vec::new(), state: None }
+    }
+
+    /// Sets who "me" is for `list --mine` and a bare `assign`.
+    pub fn with_user(mut self, user: Option<String>) -> Commands {
+        self.user = user;
+        self
+    }
+
+    /// Sets the weights used for `next` and `list --sort urgency`.
+    pub fn with_urgency(mut self, urgency: Urgency) -> Commands {
+        self.urgency = urgency;
+        self
+    }
+
+    pub fn with_contexts(mut self, contexts: Vec<ContextDef>) -> Commands {
+        self.contexts = contexts;
+        self
+    }
+
+    /// Sets where this user's own settings, like the active context, are kept.
+    pub fn with_state(mut self, state: State) -> Commands {
+        self.state = Some(state);
+        self
+    }
+
+    // The active context. One that has since been removed from the config
+    // is ignored.
+    fn active_context(&self) -> Option<&ContextDef> {
+        let name = self.state.as_ref()?.context()?;
+        self.contexts.iter().find(|context| context.name == name)
+    }
+
+    fn in_context(&self, tasks: Vec<Task>) -> Vec<Task> {
+        match self.active_context() {
+            Some(context) => tasks.into_iter().filter(|t| context.matches(t)).collect(),
+            None => tasks,
+        }
+    }
+
+    pub fn list_contexts(&self) {
+        if self.contexts.is_empty() {
+            println!("\n\x1b[93m🐾 No contexts yet! Add a [context <name>] section to ~/.trackr_config\x1b[0m\n");
+            return;
+        }
+
+        let active = self.active_context().map(|context| context.name.clone());
+        println!("\n\x1b[96m🎯 Your contexts...\x1b[0m\n");
+        for context in &self.contexts {
+            let marker = if active.as_ref() == Some(&context.name) { "▶" } else { " " };
+            let mut filter: Vec<String> = context.tags.iter().map(|tag| format!("+{}", tag)).collect();
+            filter.extend(context.project.iter().map(|project| format!("project:{}", project)));
+            println!("\x1b[97m{} {:<15}\x1b[0m \x1b[90m{}\x1b[0m", marker, context.name, filter.join(" or "));
+        }
+        println!();
+    }
+
+    /// Makes `name` the active context, or clears it with `None`.
+    pub fn set_context(&self, name: Option<&str>) {
+        let name = name.map(|name| name.to_lowercase());
+        if let Some(name) = &name
+            && !self.contexts.iter().any(|context| &context.name == name)
+        {
+            let names: Vec<&str> = self.contexts.iter().map(|context| context.name.as_str()).collect();
+            println!("\n\x1b[91m😿 Unknown context '{}'! Use: {}\x1b[0m\n", name,
+                if names.is_empty() { "none defined in ~/.trackr_config".to_string() } else { names.join(", ") });
+            return;
+        }
+
+        let Some(state) = &self.state else {
+            println!("\n\x1b[91m😿 Error: There's nowhere to keep the active context!\x1b[0m\n");
+            return;
+        };
+
+        match state.set_context(name.as_deref()) {
+            Ok(_) => match name {
+                Some(name) => println!("\n\x1b[95m🎯 Switched to the {} context!\x1b[0m\n", name),
+                None => println!("\n\x1b[95m🎯 Context cleared, seeing everything again!\x1b[0m\n"),
+            },
+            Err(e) => {
+                self.print_save_error(&e);
+            }
+        }
+    }
+
+    pub fn add(&self, description: String) {
+        self.add_with_options(description, TaskOptions::default());
+    }
+
+    pub fn add_with_options(&self, description: String, mut options: TaskOptions) {
+        if let Some(context) = self.active_context() {
+            options.tags.extend(context.tags.iter().cloned());
+            if options.project.is_none() {
+                options.project = context.project.clone();
+            }
+        }
+
+        let mut tasks = self.storage.load_tasks();
+        let Some(next_id) = self.storage.next_id(&tasks) else {
+            println!("\n\x1b[91m😿 No ids left! Every id up to {} has been used\x1b[0m\n", u32::MAX);
+            return;
+        };
+
+        let Ok(parent) = self.resolve_parent(&tasks, None, options.parent.as_deref()) else {
+            return;
+        };
+
+        let now = datetime::now();
+        let mut task = Task::new(next_id, description.clone());
+        task.created_at = Some(now);
+        task.touch(now);
+        if !self.apply_options(&mut task, &options) {
+            return;
+        }
+        if let Some(parent) = parent {
+            task.parent = parent;
+        }
+        tasks.push(task);
+
+        match self.storage.save_tasks(&tasks) {
+            Ok(_) => {
+                println!("\n\x1b[95m😸 Task added successfully, slay!\x1b[0m");
+                println!("\x1b[96m   ID: {} | {}\x1b[0m\n", next_id, description);
+            }
+            Err(e) => {
+                self.print_save_error(&e);
+            }
+        }
+    }
+
+    pub fn update(&self, id: u32, new_description: String) {
+        self.update_with_options(id, Some(new_description), TaskOptions::default());
+    }
+
+    pub fn update_with_options(&self, id: u32, new_description: Option<String>, options: TaskOptions) {
+        let mut tasks = self.storage.load_tasks();
+        let Ok(parent) = self.resolve_parent(&tasks, Some(id), options.parent.as_deref()) else {
+            return;
+        };
+
+        if let Some(task) = tasks.iter_mut().find(|t| t.id == id) {
+            if let Some(new_description) = new_description {
+                task.description = new_description;
+            }
+            if !self.apply_options(task, &options) {
+                return;
+            }
+            if let Some(parent) = parent {
+                task.parent = parent;
+            }
+            task.touch(datetime::now());
+            let description = task.description.clone();
+
+            match self.storage.save_tasks(&tasks) {
+                Ok(_) => {
+                    println!("\n\x1b[95m✨ Task updated, you're killing it!\x1b[0m");
+                    println!("\x1b[96m   ID: {} | {}\x1b[0m\n", id, description);
+                }
+                Err(e) => {
+                    self.print_save_error(&e);
+                }
+            }
+        } else {
+            println!("\n\x1b[91m😿 Task not found, meow again!\x1b[0m\n");
+        }
+    }
+
+    /// Resolves a numeric id or UUID prefix given on the command line.
+    /// Archived and trashed tasks count too, so they can be shown and restored.
+    pub fn resolve_id(&self, reference: &str) -> Result<u32, String> {
+        let mut tasks = self.storage.load_tasks();
+        tasks.extend(self.storage.load_archive());
+        tasks.extend(self.storage.load_trash());
+        resolve_id(&tasks, reference)
+    }
+
+    // Validates a `--parent` value. `Ok(None)` means leave the parent alone,
+    // `Ok(Some(None))` clears it.
+    fn resolve_parent(&self, tasks: &[Task], task_id: Option<u32>, value: Option<&str>) -> Result<Option<Option<u32>>, ()> {
+        let Some(value) = value else {
+            return Ok(None);
+        };
+        if value == "none" {
+            return Ok(Some(None));
+        }
+
+        let parent_id = match resolve_id(tasks, value) {
+            Ok(parent_id) => parent_id,
+            Err(e) => {
+                println!("\n\x1b[91m😿 Invalid parent: {} (or none to clear)\x1b[0m\n", e);
+                return Err(());
+            }
+        };
+        if !tasks.iter().any(|t| t.id == parent_id) {
+            println!("\n\x1b[91m😿 Parent task #{} not found, meow again!\x1b[0m\n", parent_id);
+            return Err(());
+        }
+        if let Some(task_id) = task_id
+            && (parent_id == task_id || descendants_of(tasks, task_id).contains(&parent_id))
+        {
+            println!("\n\x1b[91m😿 Task #{} can't be its own ancestor!\x1b[0m\n", task_id);
+            return Err(());
+        }
+
+        Ok(Some(Some(parent_id)))
+    }
+
+    fn apply_options(&self, task: &mut Task, options: &TaskOptions) -> bool {
+        if let Some(due_str) = &options.due {
+            if due_str == "none" {
+                task.due = None;
+            } else if let Some(due) = Date::parse(due_str) {
+                task.due = Some(due);
+            } else {
+                println!("\n\x1b[91m😿 Invalid due date! Use: YYYY-MM-DD (or none to clear)\x1b[0m\n");
+                return false;
+            }
+        }
+
+        if let Some(wait_str) = &options.wait {
+            if wait_str == "none" {
+                task.wait = None;
+            } else if let Some(wait) = Date::parse(wait_str) {
+                task.wait = Some(wait);
+            } else {
+                println!("\n\x1b[91m😿 Invalid wait date! Use: YYYY-MM-DD (or none to clear)\x1b[0m\n");
+                return false;
+            }
+        }
+
+        if let Some(priority_str) = &options.priority {
+            if priority_str == "none" {
+                task.priority = None;
+            } else if let Some(priority) = Priority::from_str(priority_str) {
+                task.priority = Some(priority);
+            } else {
+                println!("\n\x1b[91m😿 Invalid priority! Use: low, medium, high, critical (or none to clear)\x1b[0m\n");
+                return false;
+            }
+        }
+
+        for tag in &options.tags {
+            if !Task::is_valid_tag(tag) {
+                println!("\n\x1b[91m😿 Invalid tag '{}'! Tags can't contain spaces, commas or quotes\x1b[0m\n", tag);
+                return false;
+            }
+            task.add_tag(tag);
+        }
+
+        if let Some(project) = &options.project {
+            if project == "none" {
+                task.project = None;
+            } else if Task::is_valid_project(project) {
+                task.project = Some(project.clone());
+            } else {
+                println!("\n\x1b[91m😿 Invalid project '{}'! Use dotted names like infra.ci.runners\x1b[0m\n", project);
+                return false;
+            }
+        }
+
+        if let Some(recur_str) = &options.recur {
+            if recur_str == "none" {
+                task.recurrence = None;
+            } else if let Some(recurrence) = Recurrence::from_str(recur_str) {
+                task.recurrence = Some(recurrence);
+            } else {
+                println!("\n\x1b[91m😿 Invalid recurrence! Use: daily, weekly, weekly:mon,thu, monthly, monthly:N or every:N\x1b[0m\n");
+                return false;
+            }
+        }
+
+        for assignment in &options.set {
+            match self.parse_uda_assignment(assignment) {
+                Ok((name, Some(value))) => {
+                    task.udas.insert(name, value);
+                }
+                Ok((name, None)) => {
+                    task.udas.remove(&name);
+                }
+                Err(e) => {
+                    println!("\n\x1b[91m😿 {}\x1b[0m\n", e);
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    // Splits `name=value` and checks the value against the attribute's type.
+    // A value of `none` gives `None`.
+    fn parse_uda_assignment(&self, assignment: &str) -> Result<(String, Option<UdaValue>), String> {
+        let Some((name, raw)) = assignment.split_once('=') else {
+            return Err(format!("Invalid attribute '{}'! Use: name=value", assignment));
+        };
+        let Some(def) = self.storage.uda(name.trim()) else {
+            return Err(format!("Unknown attribute '{}'! Declare it under [uda {}] in ~/.trackr_config", name, name));
+        };
+
+        if raw.trim() == "none" {
+            return Ok((def.name.clone(), None));
+        }
+        def.parse_value(raw).map(|value| (def.name.clone(), Some(value)))
+    }
+
+    pub fn delete(&self, id: u32) {
+        self.delete_with_children(id, None);
+    }
+
+    /// Deletes a task. If it has subtasks, `children` decides what happens to
+    /// them; `None` asks interactively.
+    pub fn delete_with_children(&self, id: u32, children: Option<ChildAction>) {
+        let mut tasks = self.storage.load_tasks();
+
+        let Some(grandparent) = tasks.iter().find(|t| t.id == id).map(|t| t.parent) else {
+            println!("\n\x1b[91m😿 Task not found, meow again!\x1b[0m\n");
+            return;
+        };
+
+        let child_count = children_of(&tasks, id).len();
+        let mut removed = vec![id];
+
+        if child_count > 0 {
+            let action = match children {
+                Some(action) => action,
+                None => match self.ask_child_action(id, child_count) {
+                    Some(action) => action,
+                    None => {
+                        println!("\n\x1b[93m🐾 Delete cancelled, task #{} lives on!\x1b[0m\n", id);
+                        return;
+                    }
+                },
+            };
+
+            match action {
+                ChildAction::Remove => removed.extend(descendants_of(&tasks, id)),
+                ChildAction::Reparent => {
+                    let now = datetime::now();
+                    for task in tasks.iter_mut().filter(|t| t.parent == Some(id)) {
+                        task.parent = grandparent;
+                        task.touch(now);
+                    }
+                }
+            }
+        }
+
+        let now = datetime::now();
+        // Dependencies on the deleted tasks are kept, so restoring them puts
+        // everything back. Until then they count as satisfied.
+        let (mut deleted, tasks): (Vec<Task>, Vec<Task>) = tasks.into_iter()
+            .partition(|t| removed.contains(&t.id));
+        for task in deleted.iter_mut() {
+            task.stop_timer(now);
+            task.deleted_at = Some(now);
+        }
+
+        let mut trash = self.storage.load_trash();
+        trash.extend(deleted);
+
+        match self.storage.save_with_trash(&tasks, &trash) {
+            Ok(_) => {
+                println!("\n\x1b[95m🗑️  Task deleted! Bye bye task #{}\x1b[0m", id);
+                if removed.len() > 1 {
+                    println!("\x1b[96m   Also removed {} subtask(s)\x1b[0m", removed.len() - 1);
+                } else if child_count > 0 {
+                    println!("\x1b[96m   Moved {} subtask(s) up a level\x1b[0m", child_count);
+                }
+                println!("\x1b[90m   Changed your mind? trackr restore {}\x1b[0m\n", id);
+            }
+            Err(e) => {
+                self.print_save_error(&e);
+            }
+        }
+    }
+
+    pub fn archive(&self, id: u32) {
+        let tasks = self.storage.load_tasks();
+
+        let Some(task) = tasks.iter().find(|t| t.id == id) else {
+            println!("\n\x1b[91m😿 Task not found, meow again!\x1b[0m\n");
+            return;
+        };
+        if !task.status.is_closed() {
+            println!("\n\x1b[91m😿 Task #{} isn't finished yet! Only closed tasks can be archived\x1b[0m\n", id);
+            return;
+        }
+        let open_children = tasks.iter()
+            .filter(|t| t.parent == Some(id) && !t.status.is_closed())
+            .count();
+        if open_children > 0 {
+            println!("\n\x1b[91m😿 Task #{} still has {} open subtask(s)!\x1b[0m\n", id, open_children);
+            return;
+        }
+
+        self.move_to_archive(tasks, &[id]);
+    }
+
+    /// Archives every closed task that has no open subtasks.
+    pub fn archive_done(&self) {
+        let tasks = self.storage.load_tasks();
+        let ids: Vec<u32> = tasks.iter()
+            .filter(|t| t.status.is_closed())
+            .filter(|t| !tasks.iter().any(|c| c.parent == Some(t.id) && !c.status.is_closed()))
+            .map(|t| t.id)
+            .collect();
+
+        if ids.is_empty() {
+            println!("\n\x1b[93m🐾 Nothing to archive! Finish some tasks first\x1b[0m\n");
+            return;
+        }
+
+        self.move_to_archive(tasks, &ids);
+    }
+
+    fn move_to_archive(&self, tasks: Vec<Task>, ids: &[u32]) {
+        let mut archive = self.storage.load_archive();
+        let (moved, kept): (Vec<Task>, Vec<Task>) = tasks.into_iter().partition(|t| ids.contains(&t.id));
+        archive.extend(moved);
+
+        match self.storage.save_with_archive(&kept, &archive) {
+            Ok(_) => {
+                if ids.len() == 1 {
+                    println!("\n\x1b[95m📦 Task archived! #{} is tucked away safely\x1b[0m", ids[0]);
+                } else {
+                    println!("\n\x1b[95m📦 Archived {} finished tasks! So tidy!\x1b[0m", ids.len());
+                }
+                println!("\x1b[96m   See them with trackr list --archived\x1b[0m\n");
+            }
+            Err(e) => {
+                self.print_save_error(&e);
+            }
+        }
+    }
+
+    pub fn unarchive(&self, id: u32) {
+        let mut archive = self.storage.load_archive();
+
+        let Some(index) = archive.iter().position(|t| t.id == id) else {
+            println!("\n\x1b[91m😿 Task #{} isn't in the archive!\x1b[0m\n", id);
+            return;
+        };
+
+        let task = archive.remove(index);
+        let description = task.description.clone();
+        let mut tasks = self.storage.load_tasks();
+        let position = tasks.iter().position(|t| t.id > id).unwrap_or(tasks.len());
+        tasks.insert(position, task);
+
+        match self.storage.save_with_archive(&tasks, &archive) {
+            Ok(_) => {
+                println!("\n\x1b[95m📤 Task restored! Welcome back!\x1b[0m");
+                println!("\x1b[96m   ID: {} | {}\x1b[0m\n", id, description);
+            }
+            Err(e) => {
+                self.print_save_error(&e);
+            }
+        }
+    }
+
+    pub fn trash(&self) {
+        let trash = self.storage.load_trash();
+
+        if trash.is_empty() {
+            println!("\n\x1b[93m🐾 The trash is empty!\x1b[0m\n");
+            return;
+        }
+
+        let now = datetime::now();
+        println!("\n\x1b[96m🗑️  Rummaging through the trash...\x1b[0m\n");
+        println!("\x1b[90m{:<6} {:<12} DESCRIPTION\x1b[0m", "ID", "DELETED");
+        println!("\x1b[90m{}\x1b[0m", "─".repeat(60));
+        for task in &trash {
+            let deleted = task.deleted_at
+                .map(|at| datetime::format_ago(now.saturating_sub(at)))
+                .unwrap_or_else(|| "-".to_string());
+            println!("\x1b[90m{:<6} {:<12} {}\x1b[0m", task.id, deleted, task.description);
+        }
+        println!("\n\x1b[90mBring one back with trackr restore <id>\x1b[0m\n");
+    }
+
+    /// Brings a task back from the trash, along with any of its subtasks
+    /// that were deleted too.
+    pub fn restore(&self, id: u32) {
+        let mut trash = self.storage.load_trash();
+
+        if !trash.iter().any(|t| t.id == id) {
+            println!("\n\x1b[91m😿 Task #{} isn't in the trash!\x1b[0m\n", id);
+            return;
+        }
+
+        let mut ids = descendants_of(&trash, id);
+        ids.push(id);
+        let (restored, kept): (Vec<Task>, Vec<Task>) = trash.drain(..).partition(|t| ids.contains(&t.id));
+        trash = kept;
+
+        let mut tasks = self.storage.load_tasks();
+        let mut description = String::new();
+        for mut task in restored {
+            task.deleted_at = None;
+            if task.id == id {
+                description = task.description.clone();
+                // The parent may have been deleted or archived since.
+                if task.parent.is_some_and(|parent| !tasks.iter().any(|t| t.id == parent)) {
+                    task.parent = None;
+                }
+            }
+            let position = tasks.iter().position(|t| t.id > task.id).unwrap_or(tasks.len());
+            tasks.insert(position, task);
+        }
+
+        match self.storage.save_with_trash(&tasks, &trash) {
+            Ok(_) => {
+                println!("\n\x1b[95m♻️  Task restored! Welcome back!\x1b[0m");
+                println!("\x1b[96m   ID: {} | {}\x1b[0m", id, description);
+                if ids.len() > 1 {
+                    println!("\x1b[96m   Also restored {} subtask(s)\x1b[0m", ids.len() - 1);
+                }
+                println!();
+            }
+            Err(e) => {
+                self.print_save_error(&e);
+            }
+        }
+    }
+
+    /// Permanently removes trashed tasks, or only those deleted more than
+    /// `older_than` seconds ago.
+    pub fn empty_trash(&self, older_than: Option<u64>) {
+        let now = datetime::now();
+        let (purged, mut trash): (Vec<Task>, Vec<Task>) = self.storage.load_trash().into_iter()
+            .partition(|t| older_than.is_none_or(|older_than| t.deleted_at.is_some_and(|at| now.saturating_sub(at) > older_than)));
+
+        if purged.is_empty() {
+            println!("\n\x1b[93m🐾 Nothing to purge!\x1b[0m\n");
+            return;
+        }
+
+        // Purged tasks can't come back, so dependencies on them go too.
+        let mut tasks = self.storage.load_tasks();
+        for task in tasks.iter_mut().chain(trash.iter_mut()) {
+            task.depends_on.retain(|dep| !purged.iter().any(|t| t.id == *dep));
+        }
+
+        match self.storage.save_with_trash(&tasks, &trash) {
+            Ok(_) => {
+                println!("\n\x1b[95m🔥 Purged {} task(s) for good!\x1b[0m\n", purged.len());
+            }
+            Err(e) => {
+                self.print_save_error(&e);
+            }
+        }
+    }
+
+    fn ask_child_action(&self, id: u32, child_count: usize) -> Option<ChildAction> {
+        println!("\n\x1b[93m🐾 Task #{} has {} subtask(s).\x1b[0m", id, child_count);
+        let answer = self.prompt("\x1b[93m   [r]emove them too, re-[p]arent them, or [c]ancel? \x1b[0m")?;
+        ChildAction::from_str(&answer)
+    }
+
+    fn prompt(&self, question: &str) -> Option<String> {
+        print!("{}", question);
+        io::stdout().flush().ok();
+
+        let mut answer = String::new();
+        match io::stdin().read_line(&mut answer) {
+            Ok(0) | Err(_) => None,
+            Ok(_) => Some(answer.trim().to_string()),
+        }
+    }
+
+    pub fn mark(&self, id: u32, status_str: String) {
+        let workflow = self.storage.workflow();
+        let Some(status) = workflow.status(&status_str) else {
+            println!("\n\x1b[91m😿 Invalid status! Use: {}\x1b[0m\n", workflow.names().join(", "));
+            return;
+        };
+
+        let mut tasks = self.storage.load_tasks();
+        let mut found = false;
+        let mut description = String::new();
+        let mut spawned = None;
+        let mut out_of_ids = false;
+        let now = datetime::now();
+        let next_id = self.storage.next_id(&tasks);
+
+        for task in tasks.iter_mut() {
+            if task.id == id {
+                if !workflow.can_transition(&task.status, &status) {
+                    self.print_transition_error(task, &status);
+                    return;
+                }
+                let was_done = task.status == TaskStatus::Done;
+                task.set_status(status.clone(), now);
+                description = task.description.clone();
+                if status != TaskStatus::InProgress {
+                    task.stop_timer(now);
+                }
+                if status == TaskStatus::Done && !was_done {
+                    match next_id {
+                        Some(next_id) => spawned = task.next_occurrence(next_id, Date::today(), now),
+                        None => out_of_ids = task.recurrence.is_some(),
+                    }
+                }
+                found = true;
+                break;
+            }
+        }
+
+        // Don't respawn if the series already has an open instance, e.g. when
+        // a finished task is reopened and finished again.
+        let mut queued = None;
+        if let Some(next) = spawned {
+            let series_open = tasks.iter().any(|t| {
+                !t.status.is_closed() && t.recurrence.is_some()
+                    && t.recur_from.unwrap_or(t.id) == next.recur_from.unwrap_or(next.id)
+            });
+            if !series_open {
+                queued = Some((next.id, next.due));
+                tasks.push(next);
+            }
+        }
+
+        if found {
+            let open_children = tasks.iter()
+                .filter(|t| t.parent == Some(id) && !t.status.is_closed())
+                .count();
+            let blockers = tasks.iter()
+                .find(|t| t.id == id)
+                .map(|task| open_blockers(&tasks, task))
+                .unwrap_or_default();
+
+            match self.storage.save_tasks(&tasks) {
+                Ok(_) => {
+                    println!("\n\x1b[95m{} Task marked as {}! Keep going!\x1b[0m",
+                        workflow.emoji(&status), status.to_string());
+                    println!("\x1b[96m   ID: {} | {}\x1b[0m\n", id, description);
+                    if status.is_closed() && open_children > 0 {
+                        println!("\x1b[93m⚠️  Heads up: task #{} still has {} open subtask(s)\x1b[0m\n",
+                            id, open_children);
+                    }
+                    if let Some((next_id, Some(next_due))) = queued {
+                        println!("\x1b[96m🔁 Next one is already queued: task #{} due {}\x1b[0m\n",
+                            next_id, next_due);
+                    }
+                    if out_of_ids {
+                        println!("\x1b[93m⚠️  No ids left, so the next one couldn't be queued\x1b[0m\n");
+                    }
+                    if status == TaskStatus::InProgress && !blockers.is_empty() {
+                        println!("\x1b[93m⚠️  Heads up: task #{} is still blocked by {}\x1b[0m\n",
+                            id, self.format_ids(&blockers));
+                    }
+                }
+                Err(e) => {
+                    self.print_save_error(&e);
+                }
+            }
+        } else {
+            println!("\n\x1b[91m😿 Task not found, meow again!\x1b[0m\n");
+        }
+    }
+
+    pub fn status_names(&self) -> Vec<&str> {
+        self.storage.workflow().names()
+    }
+
+    fn print_transition_error(&self, task: &Task, to: &TaskStatus) {
+        let allowed = self.storage.workflow().allowed_from(&task.status)
+            .map(|targets| targets.join(", "))
+            .filter(|targets| !targets.is_empty())
+            .unwrap_or_else(|| "nothing".to_string());
+        println!("\n\x1b[91m😿 Task #{} can't go from {} to {}! Allowed from {}: {}\x1b[0m\n",
+            task.id, task.status.to_string(), to.to_string(), task.status.to_string(), allowed);
+    }
+
+    pub fn start(&self, id: u32) {
+        let mut tasks = self.storage.load_tasks();
+        let now = datetime::now();
+
+        let Some(index) = tasks.iter().position(|t| t.id == id) else {
+            println!("\n\x1b[91m😿 Task not found, meow again!\x1b[0m\n");
+            return;
+        };
+        if tasks[index].is_tracking() {
+            println!("\n\x1b[93m⏱️  Timer is already running on task #{}\x1b[0m\n", id);
+            return;
+        }
+        if !self.storage.workflow().can_transition(&tasks[index].status, &TaskStatus::InProgress) {
+            self.print_transition_error(&tasks[index], &TaskStatus::InProgress);
+            return;
+        }
+
+        // Only one timer runs at a time.
+        let mut stopped = Vec::new();
+        for task in tasks.iter_mut() {
+            if let Some(duration) = task.stop_timer(now) {
+                stopped.push((task.id, duration));
+            }
+        }
+
+        let task = &mut tasks[index];
+        task.start_timer(now);
+        if task.status != TaskStatus::InProgress {
+            task.set_status(TaskStatus::InProgress, now);
+        }
+        let description = task.description.clone();
+        let blockers = open_blockers(&tasks, &tasks[index]);
+
+        match self.storage.save_tasks(&tasks) {
+            Ok(_) => {
+                for (stopped_id, duration) in stopped {
+                    println!("\n\x1b[90m⏹️  Stopped timer on task #{} after {}\x1b[0m",
+                        stopped_id, datetime::format_duration(duration));
+                }
+                println!("\n\x1b[95m⏱️  Timer started! Let's get to work!\x1b[0m");
+                println!("\x1b[96m   ID: {} | {}\x1b[0m\n", id, description);
+                if !blockers.is_empty() {
+                    println!("\x1b[93m⚠️  Heads up: task #{} is still blocked by {}\x1b[0m\n",
+                        id, self.format_ids(&blockers));
+                }
+            }
+            Err(e) => {
+                self.print_save_error(&e);
+            }
+        }
+    }
+
+    /// Stops the running timer, optionally only if it belongs to `id`.
+    pub fn stop(&self, id: Option<u32>) {
+        let mut tasks = self.storage.load_tasks();
+        let now = datetime::now();
+
+        if let Some(id) = id && !tasks.iter().any(|t| t.id == id) {
+            println!("\n\x1b[91m😿 Task not found, meow again!\x1b[0m\n");
+            return;
+        }
+
+        let Some(task) = tasks.iter_mut()
+            .find(|t| t.is_tracking() && id.is_none_or(|id| t.id == id)) else {
+            match id {
+                Some(id) => println!("\n\x1b[93m🐾 No timer running on task #{}\x1b[0m\n", id),
+                None => println!("\n\x1b[93m🐾 No timer running! Start one with trackr start <id>\x1b[0m\n"),
+            }
+            return;
+        };
+
+        let duration = task.stop_timer(now).unwrap_or(0);
+        let (stopped_id, description, total) = (task.id, task.description.clone(), task.time_spent(now));
+
+        match self.storage.save_tasks(&tasks) {
+            Ok(_) => {
+                println!("\n\x1b[95m⏹️  Timer stopped after {}! Nice work!\x1b[0m",
+                    datetime::format_duration(duration));
+                println!("\x1b[96m   ID: {} | {} | Total: {}\x1b[0m\n",
+                    stopped_id, description, datetime::format_duration(total));
+            }
+            Err(e) => {
+                self.print_save_error(&e);
+            }
+        }
+    }
+
+    pub fn prio(&self, id: u32, priority_str: String) {
+        let priority = if priority_str == "none" {
+            None
+        } else {
+            match Priority::from_str(&priority_str) {
+                Some(p) => Some(p),
+                None => {
+                    println!("\n\x1b[91m😿 Invalid priority! Use: low, medium, high, critical (or none to clear)\x1b[0m\n");
+                    return;
+                }
+            }
+        };
+
+        let mut tasks = self.storage.load_tasks();
+
+        if let Some(task) = tasks.iter_mut().find(|t| t.id == id) {
+            task.priority = priority;
+            task.touch(datetime::now());
+            let description = task.description.clone();
+
+            match self.storage.save_tasks(&tasks) {
+                Ok(_) => {
+                    let label = priority.map(|p| p.to_string()).unwrap_or_else(|| "none".to_string());
+                    println!("\n\x1b[95m🔥 Priority set to {}! Main character energy.\x1b[0m", label);
+                    println!("\x1b[96m   ID: {} | {}\x1b[0m\n", id, description);
+                }
+                Err(e) => {
+                    self.print_save_error(&e);
+                }
+            }
+        } else {
+            println!("\n\x1b[91m😿 Task not found, meow again!\x1b[0m\n");
+        }
+    }
+
+    /// Assigns a task to `name`, or to the current user if no name is
+    /// given. `none` unassigns it.
+    pub fn assign(&self, id: u32, name: Option<String>) {
+        let assignee = match name.as_deref() {
+            Some("none") => None,
+            Some(name) if Task::is_valid_assignee(name) => Some(name.to_string()),
+            Some(name) => {
+                println!("\n\x1b[91m😿 Invalid assignee '{}'! Names can't contain spaces, commas or quotes\x1b[0m\n", name);
+                return;
+            }
+            None => match &self.user {
+                Some(user) => Some(user.clone()),
+                None => {
+                    self.print_unknown_user();
+                    return;
+                }
+            },
+        };
+
+        let mut tasks = self.storage.load_tasks();
+
+        if let Some(task) = tasks.iter_mut().find(|t| t.id == id) {
+            task.assignee = assignee.clone();
+            task.touch(datetime::now());
+            let description = task.description.clone();
+
+            match self.storage.save_tasks(&tasks) {
+                Ok(_) => {
+                    match assignee {
+                        Some(assignee) => println!("\n\x1b[95m🙋 Task assigned to {}! Go get 'em!\x1b[0m", assignee),
+                        None => println!("\n\x1b[95m🙋 Task unassigned! Up for grabs.\x1b[0m"),
+                    }
+                    println!("\x1b[96m   ID: {} | {}\x1b[0m\n", id, description);
+                }
+                Err(e) => {
+                    self.print_save_error(&e);
+                }
+            }
+        } else {
+            println!("\n\x1b[91m😿 Task not found, meow again!\x1b[0m\n");
+        }
+    }
+
+    // A file that couldn't be loaded is never saved over, so say how to get
+    // it back into shape.
+    fn print_save_error(&self, e: &StorageError) {
+        println!("\n\x1b[91m😿 Failed to save: {}\x1b[0m", e);
+        match e {
+            StorageError::Corrupt { .. } | StorageError::Unreadable { .. } => {
+                println!("\x1b[93m   Nothing was changed. Run trackr recover to back the file up and keep what's readable\x1b[0m\n");
+            }
+            _ => {
+                println!("\x1b[93m   Your task file was left as it was\x1b[0m\n");
+            }
+        }
     }
 
-    pub fn add(&self, description: String) {
+    fn print_unknown_user(&self) {
+        println!("\n\x1b[91m😿 Who are you? Set name under [user] in ~/.trackr_config or set $USER\x1b[0m\n");
+    }
+
+    pub fn depend(&self, id: u32, on_id: u32) {
         let mut tasks = self.storage.load_tasks();
-        let next_id = tasks.iter().map(|t| t.id).max().unwrap_or(0) + 1;
 
-        let task = Task::new(next_id, description.clone());
-        tasks.push(task);
+        if !tasks.iter().any(|t| t.id == on_id) {
+            println!("\n\x1b[91m😿 Task #{} not found, meow again!\x1b[0m\n", on_id);
+            return;
+        }
+        if id == on_id || depends_transitively(&tasks, on_id, id) {
+            println!("\n\x1b[91m😿 That would create a dependency cycle! #{} already depends on #{}\x1b[0m\n",
+                on_id, id);
+            return;
+        }
 
-        match self.storage.save_tasks(&tasks) {
-            Ok(_) => {
-                println!("\n\x1b[95m😸 Task added successfully, slay!\x1b[0m");
-                println!("\x1b[96m   ID: {} | {}\x1b[0m\n", next_id, description);
+        if let Some(task) = tasks.iter_mut().find(|t| t.id == id) {
+            if !task.depends_on.contains(&on_id) {
+                task.depends_on.push(on_id);
+                task.touch(datetime::now());
             }
-            Err(e) => {
-                println!("\n\x1b[91m😿 Oops! Failed to save: {}\x1b[0m\n", e);
+
+            match self.storage.save_tasks(&tasks) {
+                Ok(_) => {
+                    println!("\n\x1b[95m🔗 Task #{} now waits on task #{}\x1b[0m\n", id, on_id);
+                }
+                Err(e) => {
+                    self.print_save_error(&e);
+                }
             }
+        } else {
+            println!("\n\x1b[91m😿 Task not found, meow again!\x1b[0m\n");
         }
     }
 
-    pub fn update(&self, id: u32, new_description: String) {
+    pub fn undepend(&self, id: u32, on_id: u32) {
         let mut tasks = self.storage.load_tasks();
 
         if let Some(task) = tasks.iter_mut().find(|t| t.id == id) {
-            task.description = new_description.clone();
+            if !task.depends_on.contains(&on_id) {
+                println!("\n\x1b[91m😿 Task #{} doesn't depend on task #{}\x1b[0m\n", id, on_id);
+                return;
+            }
+            task.depends_on.retain(|dep| *dep != on_id);
+            task.touch(datetime::now());
 
             match self.storage.save_tasks(&tasks) {
                 Ok(_) => {
-                    println!("\n\x1b[95m✨ Task updated, you're killing it!\x1b[0m");
-                    println!("\x1b[96m   ID: {} | {}\x1b[0m\n", id, new_description);
+                    println!("\n\x1b[95m✂️  Task #{} no longer waits on task #{}\x1b[0m\n", id, on_id);
                 }
                 Err(e) => {
-                    println!("\n\x1b[91m😿 Failed to save: {}\x1b[0m\n", e);
+                    self.print_save_error(&e);
                 }
             }
         } else {
@@ -49,19 +1015,45 @@ impl Commands {
         }
     }
 
-    pub fn delete(&self, id: u32) {
+    pub fn tag(&self, id: u32, changes: Vec<String>) {
+        let mut additions = Vec::new();
+        let mut removals = Vec::new();
+
+        for change in &changes {
+            let (target, tag) = if let Some(tag) = change.strip_prefix('-') {
+                (&mut removals, tag)
+            } else {
+                (&mut additions, change.strip_prefix('+').unwrap_or(change))
+            };
+
+            if !Task::is_valid_tag(tag) {
+                println!("\n\x1b[91m😿 Invalid tag '{}'! Use +tag to add and -tag to remove\x1b[0m\n", change);
+                return;
+            }
+            target.push(tag.to_string());
+        }
+
         let mut tasks = self.storage.load_tasks();
-        let initial_len = tasks.len();
 
-        tasks.retain(|t| t.id != id);
+        if let Some(task) = tasks.iter_mut().find(|t| t.id == id) {
+            for tag in &additions {
+                task.add_tag(tag);
+            }
+            for tag in &removals {
+                task.remove_tag(tag);
+            }
+            task.touch(datetime::now());
+
+            let description = task.description.clone();
+            let tags = self.format_tags(task);
 
-        if tasks.len() < initial_len {
             match self.storage.save_tasks(&tasks) {
                 Ok(_) => {
-                    println!("\n\x1b[95m🗑️  Task deleted! Bye bye task #{}\x1b[0m\n", id);
+                    println!("\n\x1b[95m🏷️  Tags updated, looking organized!\x1b[0m");
+                    println!("\x1b[96m   ID: {} | {} {}\x1b[0m\n", id, description, tags);
                 }
                 Err(e) => {
-                    println!("\n\x1b[91m😿 Failed to save: {}\x1b[0m\n", e);
+                    self.print_save_error(&e);
                 }
             }
         } else {
@@ -69,37 +1061,62 @@ impl Commands {
         }
     }
 
-    pub fn mark(&self, id: u32, status_str: String) {
-        let status = match TaskStatus::from_str(&status_str) {
-            Some(s) => s,
-            None => {
-                println!("\n\x1b[91m😿 Invalid status! Use: todo, in-progress, or done\x1b[0m\n");
-                return;
-            }
-        };
+    pub fn tags(&self) {
+        let tasks = self.in_context(self.storage.load_tasks());
+        let counts = tag_counts(&tasks);
+
+        if counts.is_empty() {
+            println!("\n\x1b[93m🐾 No tags yet! Try trackr tag <id> +something\x1b[0m\n");
+            return;
+        }
+
+        println!("\n\x1b[96m🏷️  Your tags...\x1b[0m\n");
+        println!("\x1b[90m{:<20} TASKS\x1b[0m", "TAG");
+        println!("\x1b[90m{}\x1b[0m", "─".repeat(30));
 
+        for (tag, count) in counts {
+            println!("\x1b[97m{:<20} {}\x1b[0m", format!("+{}", tag), count);
+        }
+
+        println!();
+    }
+
+    pub fn annotate(&self, id: u32, text: String) {
         let mut tasks = self.storage.load_tasks();
-        let mut found = false;
-        let mut description = String::new();
 
-        for task in tasks.iter_mut() {
-            if task.id == id {
-                task.status = status.clone();
-                description = task.description.clone();
-                found = true;
-                break;
+        if let Some(task) = tasks.iter_mut().find(|t| t.id == id) {
+            task.annotate(text, datetime::now());
+            let count = task.annotations.len();
+
+            match self.storage.save_tasks(&tasks) {
+                Ok(_) => {
+                    println!("\n\x1b[95m📎 Note added to task #{}! Context is queen.\x1b[0m", id);
+                    println!("\x1b[96m   This task now has {} note(s)\x1b[0m\n", count);
+                }
+                Err(e) => {
+                    self.print_save_error(&e);
+                }
             }
+        } else {
+            println!("\n\x1b[91m😿 Task not found, meow again!\x1b[0m\n");
         }
+    }
+
+    pub fn denotate(&self, id: u32, position: usize) {
+        let mut tasks = self.storage.load_tasks();
+
+        if let Some(task) = tasks.iter_mut().find(|t| t.id == id) {
+            if task.denotate(position, datetime::now()).is_none() {
+                println!("\n\x1b[91m😿 Task #{} has no note #{}! Check trackr show {}\x1b[0m\n", id, position, id);
+                return;
+            }
 
-        if found {
             match self.storage.save_tasks(&tasks) {
                 Ok(_) => {
-                    println!("\n\x1b[95m{} Task marked as {}! Keep going!\x1b[0m",
-                        status.emoji(), status.to_string());
-                    println!("\x1b[96m   ID: {} | {}\x1b[0m\n", id, description);
+                    println!("\n\x1b[95m🗑️  Note #{} removed from task #{}\x1b[0m\n", position, id);
                 }
                 Err(e) => {
-                    println!("\n\x1b[91m😿 Failed to save: {}\x1b[0m\n", e);
+                    self.print_save_error(&e);
                 }
             }
         } else {
@@ -107,54 +1124,530 @@ impl Commands {
         }
     }
 
-    pub fn list(&self, filter_status: Option<String>) {
+    pub fn show(&self, id: u32) {
         let tasks = self.storage.load_tasks();
+        let elsewhere;
 
-        let filtered_tasks: Vec<&Task> = if let Some(status_str) = filter_status {
-            if let Some(status) = TaskStatus::from_str(&status_str) {
-                tasks.iter().filter(|t| t.status == status).collect()
-            } else {
-                println!("\n\x1b[91m😿 Invalid status! Use: todo, in-progress, or done\x1b[0m\n");
-                return;
+        let (task, location) = match tasks.iter().find(|t| t.id == id) {
+            Some(task) => (task, ""),
+            None => {
+                elsewhere = [self.storage.load_archive(), self.storage.load_trash()];
+                let found = elsewhere[0].iter().find(|t| t.id == id).map(|t| (t, " \x1b[90m📦 archived"))
+                    .or_else(|| elsewhere[1].iter().find(|t| t.id == id).map(|t| (t, " \x1b[90m🗑️  in the trash")));
+                let Some(found) = found else {
+                    println!("\n\x1b[91m😿 Task not found, meow again!\x1b[0m\n");
+                    return;
+                };
+                found
+            }
+        };
+
+        let or_dash = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+
+        println!("\n\x1b[96m🔎 Task #{}{}\x1b[0m\n", task.id, location);
+        println!("\x1b[90m{:<12}\x1b[0m {}", "UUID", task.uuid);
+        println!("\x1b[90m{:<12}\x1b[0m {}", "Description", task.description);
+        println!("\x1b[90m{:<12}\x1b[0m {} {}", "Status",
+            self.storage.workflow().emoji(&task.status), task.status.to_string());
+        println!("\x1b[90m{:<12}\x1b[0m {}", "Priority", or_dash(task.priority.map(|p| p.to_string())));
+        if !task.status.is_closed() {
+            let urgency = self.urgency.score(task, &tasks, Date::today(), datetime::now());
+            println!("\x1b[90m{:<12}\x1b[0m {:.2}", "Urgency", urgency);
+        }
+        println!("\x1b[90m{:<12}\x1b[0m {}", "Due", or_dash(task.due.map(|d| d.to_string())));
+        println!("\x1b[90m{:<12}\x1b[0m {}", "Wait until", or_dash(task.wait.map(|d| d.to_string())));
+        println!("\x1b[90m{:<12}\x1b[0m {}", "Project", or_dash(task.project.clone()));
+        println!("\x1b[90m{:<12}\x1b[0m {}", "Assignee", or_dash(task.assignee.clone()));
+        println!("\x1b[90m{:<12}\x1b[0m {}", "Parent", or_dash(task.parent.map(|p| format!("#{}", p))));
+        println!("\x1b[90m{:<12}\x1b[0m {}", "Recurrence", or_dash(task.recurrence.as_ref().map(|r| r.to_string())));
+        if let Some(template) = task.recur_from {
+            println!("\x1b[90m{:<12}\x1b[0m #{}", "Series of", template);
+        }
+        let now = datetime::now();
+        let running = if task.is_tracking() { " (timer running)" } else { "" };
+        println!("\x1b[90m{:<12}\x1b[0m {}{}", "Time spent",
+            or_dash(Some(task.time_spent(now)).filter(|secs| *secs > 0).map(datetime::format_duration)), running);
+        let ids_or_dash = |ids: &[u32]| or_dash(Some(self.format_ids(ids)).filter(|s| !s.is_empty()));
+        let blocks: Vec<u32> = tasks.iter().filter(|t| t.depends_on.contains(&task.id)).map(|t| t.id).collect();
+        println!("\x1b[90m{:<12}\x1b[0m {}", "Subtasks", ids_or_dash(&children_of(&tasks, task.id)));
+        println!("\x1b[90m{:<12}\x1b[0m {}", "Depends on", ids_or_dash(&task.depends_on));
+        println!("\x1b[90m{:<12}\x1b[0m {}", "Blocks", ids_or_dash(&blocks));
+        for (name, value) in &task.udas {
+            println!("\x1b[90m{:<12}\x1b[0m {}", name, value);
+        }
+        println!("\x1b[90m{:<12}\x1b[0m {}", "Tags", or_dash(Some(self.format_tags(task)).filter(|t| !t.is_empty())));
+        println!("\x1b[90m{:<12}\x1b[0m {}", "Created", or_dash(task.created_at.map(datetime::format_timestamp)));
+        println!("\x1b[90m{:<12}\x1b[0m {}", "Updated", or_dash(task.updated_at.map(datetime::format_timestamp)));
+        println!("\x1b[90m{:<12}\x1b[0m {}", "Completed", or_dash(task.completed_at.map(datetime::format_timestamp)));
+        if let Some(deleted_at) = task.deleted_at {
+            println!("\x1b[90m{:<12}\x1b[0m {}", "Deleted", datetime::format_timestamp(deleted_at));
+        }
+
+        if !task.history.is_empty() {
+            println!("\n\x1b[95m🔀 History\x1b[0m");
+            for change in &task.history {
+                println!("\x1b[90m     {}\x1b[0m  {} → {}",
+                    datetime::format_timestamp(change.at), change.from, change.to);
+            }
+        }
+
+        if task.annotations.is_empty() {
+            println!("\n\x1b[90mNo notes yet. Add one with trackr annotate {} <text>\x1b[0m\n", task.id);
+            return;
+        }
+
+        println!("\n\x1b[95m📎 Notes\x1b[0m");
+        for (i, annotation) in task.annotations.iter().enumerate() {
+            println!("\x1b[90m{:>3}. {}\x1b[0m", i + 1, datetime::format_timestamp(annotation.created_at));
+            for line in annotation.text.lines() {
+                println!("     {}", line);
             }
+        }
+        println!();
+    }
+
+    pub fn projects(&self) {
+        let tasks = self.in_context(self.storage.load_tasks());
+        let summary = project_summary(&tasks);
+
+        if summary.is_empty() {
+            println!("\n\x1b[93m🐾 No projects yet! Try trackr add <description> --project <name>\x1b[0m\n");
+            return;
+        }
+
+        println!("\n\x1b[96m📁 Your projects...\x1b[0m\n");
+        println!("\x1b[90m{:<30} {:>6} {:>12} {:>6}\x1b[0m", "PROJECT", "TODO", "IN-PROGRESS", "DONE");
+        println!("\x1b[90m{}\x1b[0m", "─".repeat(60));
+
+        for (project, counts) in summary {
+            let depth = project.matches('.').count();
+            let name = project.rsplit('.').next().unwrap_or(&project);
+            let label = format!("{}{}", "  ".repeat(depth), name);
+            println!("\x1b[97m{:<30} {:>6} {:>12} {:>6}\x1b[0m",
+                label, counts.todo, counts.in_progress, counts.done);
+        }
+
+        println!();
+    }
+
+    fn format_ids(&self, ids: &[u32]) -> String {
+        ids.iter()
+            .map(|id| format!("#{}", id))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn format_tags(&self, task: &Task) -> String {
+        task.tags.iter()
+            .map(|tag| format!("+{}", tag))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    pub fn list(&self, filter_status: Option<String>) {
+        self.list_with_options(ListOptions {
+            status: filter_status,
+            ..ListOptions::default()
+        });
+    }
+
+    pub fn list_with_options(&self, options: ListOptions) {
+        let assignee_filter = match (&options.assignee, options.mine) {
+            (Some(name), _) => Some(name.clone()),
+            (None, true) => match &self.user {
+                Some(user) => Some(user.clone()),
+                None => {
+                    self.print_unknown_user();
+                    return;
+                }
+            },
+            (None, false) => None,
+        };
+
+        let tasks = if options.archived {
+            self.storage.load_archive()
         } else {
-            tasks.iter().collect()
+            self.storage.load_tasks()
+        };
+        let context = self.active_context();
+
+        let status_filter = match options.status {
+            Some(status_str) => match self.storage.workflow().status(&status_str) {
+                Some(status) => Some(status),
+                None => {
+                    println!("\n\x1b[91m😿 Invalid status! Use: {}\x1b[0m\n",
+                        self.storage.workflow().names().join(", "));
+                    return;
+                }
+            },
+            None => None,
+        };
+
+        let due_before = match options.due_before {
+            Some(date_str) => match Date::parse(&date_str) {
+                Some(date) => Some(date),
+                None => {
+                    println!("\n\x1b[91m😿 Invalid date! Use: YYYY-MM-DD\x1b[0m\n");
+                    return;
+                }
+            },
+            None => None,
+        };
+
+        let priority_filter = match options.priority {
+            Some(priority_str) => match Priority::from_str(&priority_str) {
+                Some(priority) => Some(priority),
+                None => {
+                    println!("\n\x1b[91m😿 Invalid priority! Use: low, medium, high, or critical\x1b[0m\n");
+                    return;
+                }
+            },
+            None => None,
+        };
+
+        let mut wheres = Vec::new();
+        for condition in &options.wheres {
+            match self.parse_uda_assignment(condition) {
+                Ok(condition) => wheres.push(condition),
+                Err(e) => {
+                    println!("\n\x1b[91m😿 {}\x1b[0m\n", e);
+                    return;
+                }
+            }
+        }
+
+        let today = Date::today();
+        let mut filtered_tasks: Vec<&Task> = tasks.iter()
+            .filter(|t| context.is_none_or(|context| context.matches(t)))
+            .filter(|t| status_filter.as_ref().is_none_or(|status| &t.status == status))
+            .filter(|t| !options.overdue || t.is_overdue(today))
+            .filter(|t| due_before.is_none_or(|date| t.due.is_some_and(|due| due < date)))
+            .filter(|t| priority_filter.is_none_or(|priority| t.priority == Some(priority)))
+            .filter(|t| options.tags.iter().all(|tag| t.has_tag(tag)))
+            .filter(|t| options.project.as_ref().is_none_or(|project| t.in_project(project)))
+            .filter(|t| assignee_filter.as_ref().is_none_or(|name| t.is_assigned_to(name)))
+            .filter(|t| !options.ready || is_ready(&tasks, t))
+            .filter(|t| wheres.iter().all(|(name, value)| t.udas.get(name) == value.as_ref()))
+            .collect();
+
+        // The archive is history, so nothing in it is hidden.
+        let mut hidden = 0;
+        if !options.all && !options.archived {
+            hidden = filtered_tasks.iter().filter(|t| t.is_waiting(today)).count();
+            filtered_tasks.retain(|t| !t.is_waiting(today));
+        }
+        let hidden_note = || {
+            if hidden > 0 {
+                println!("\x1b[90m⏳ {} waiting task(s) hidden, see them with --all\x1b[0m\n", hidden);
+            }
         };
 
+        match options.sort.as_deref() {
+            None | Some("id") => {}
+            Some("priority") => filtered_tasks.sort_by_key(|t| Reverse(t.priority)),
+            Some("urgency") => {
+                let now = datetime::now();
+                let score = |t: &Task| self.urgency.score(t, &tasks, today, now);
+                filtered_tasks.sort_by(|a, b| score(b).total_cmp(&score(a)));
+            }
+            Some(name) => match self.storage.uda(name) {
+                // Tasks without the attribute go last.
+                Some(def) => filtered_tasks.sort_by(|a, b| match (a.udas.get(&def.name), b.udas.get(&def.name)) {
+                    (Some(a), Some(b)) => def.compare(a, b),
+                    (a, b) => a.is_none().cmp(&b.is_none()),
+                }),
+                None => {
+                    let mut keys = vec!["id", "priority", "urgency"];
+                    keys.extend(self.storage.udas().iter().map(|def| def.name.as_str()));
+                    println!("\n\x1b[91m😿 Invalid sort '{}'! Use: {}\x1b[0m\n", name, keys.join(", "));
+                    return;
+                }
+            },
+        }
+
+        if filtered_tasks.is_empty() && options.archived {
+            println!("\n\x1b[93m🐾 No archived tasks found!\x1b[0m\n");
+            return;
+        }
         if filtered_tasks.is_empty() {
             println!("\n\x1b[93m🐾 No tasks found! Time to add some vibes~\x1b[0m\n");
+            hidden_note();
             return;
         }
 
         println!("\n\x1b[95m{}\x1b[0m\n", Quotes::get_random());
-        println!("\x1b[96m🐾 Listing your vibes (tasks)...\x1b[0m\n");
-        println!("\x1b[90m{:<6} {:<15} {}\x1b[0m", "ID", "STATUS", "DESCRIPTION");
+        if options.archived {
+            println!("\x1b[96m📦 Digging through the archive...\x1b[0m\n");
+        } else {
+            println!("\x1b[96m🐾 Listing your vibes (tasks)...\x1b[0m\n");
+        }
+        let context = RowContext {
+            today,
+            now: datetime::now(),
+            show_age: options.show_age,
+        };
+        let age_header = if options.show_age { format!("{:<6} ", "AGE") } else { String::new() };
+        println!("\x1b[90m{:<6} {:<15} {:<10} {:<12} {:<10} {}DESCRIPTION\x1b[0m",
+            "ID", "STATUS", "PRIORITY", "DUE", "ASSIGNEE", age_header);
         println!("\x1b[90m{}\x1b[0m", "─".repeat(60));
 
-        for task in filtered_tasks {
-            let status_display = format!("{} {}", task.status.emoji(), task.status.to_string());
-            let color = match task.status {
-                TaskStatus::Todo => "\x1b[97m",
-                TaskStatus::InProgress => "\x1b[93m",
-                TaskStatus::Done => "\x1b[92m",
-            };
+        if options.group_by_project {
+            let mut groups: Vec<(Option<&String>, Vec<&Task>)> = Vec::new();
+            for task in filtered_tasks {
+                match groups.iter_mut().find(|(project, _)| *project == task.project.as_ref()) {
+                    Some((_, group)) => group.push(task),
+                    None => groups.push((task.project.as_ref(), vec![task])),
+                }
+            }
+            groups.sort_by(|(a, _), (b, _)| (a.is_none(), a).cmp(&(b.is_none(), b)));
 
-            println!("{}{:<6} {:<15} {}\x1b[0m",
-                color, task.id, status_display, task.description);
+            for (project, group) in groups {
+                let heading = project.map(|p| p.as_str()).unwrap_or("(no project)");
+                println!("\n\x1b[95m📁 {}\x1b[0m", heading);
+                self.print_tasks(&group, options.tree, &context);
+            }
+        } else {
+            self.print_tasks(&filtered_tasks, options.tree, &context);
         }
 
         println!();
+        hidden_note();
+    }
+
+    pub fn recover(&self) {
+        match self.storage.recover() {
+            Ok(None) => {
+                println!("\n\x1b[92m✅ Your task file is fine, nothing to recover!\x1b[0m\n");
+            }
+            Ok(Some(recovery)) => {
+                println!("\n\x1b[95m🩹 Task file recovered!\x1b[0m");
+                println!("\x1b[96m   The broken file is saved as {}\x1b[0m", recovery.backup_path);
+                if recovery.parsed {
+                    println!("\x1b[96m   Kept {} task(s), dropped {} that couldn't be read\x1b[0m\n",
+                        recovery.kept, recovery.dropped);
+                } else {
+                    println!("\x1b[93m   It wasn't valid JSON, so you're starting fresh\x1b[0m\n");
+                }
+            }
+            Err(e) => {
+                println!("\n\x1b[91m😿 Recovery failed: {}\x1b[0m\n", e);
+            }
+        }
+    }
+
+    /// Shows the most urgent task that can be worked on now: open, not
+    /// waiting and not blocked.
+    pub fn next(&self) {
+        let tasks = self.storage.load_tasks();
+        let today = Date::today();
+        let now = datetime::now();
+
+        let Some(task) = self.next_task(&tasks, today, now) else {
+            println!("\n\x1b[93m🐾 Nothing to do right now! Time for a catnap~\x1b[0m\n");
+            return;
+        };
+
+        println!("\n\x1b[96m👉 Up next...\x1b[0m\n");
+        println!("\x1b[90m{:<6} {:<15} {:<10} {:<12} {:<10} DESCRIPTION\x1b[0m",
+            "ID", "STATUS", "PRIORITY", "DUE", "ASSIGNEE");
+        println!("\x1b[90m{}\x1b[0m", "─".repeat(60));
+        self.print_task_row(task, "", &RowContext { today, now, show_age: false });
+        println!("\n\x1b[90mUrgency {:.2}\x1b[0m\n", self.urgency.score(task, &tasks, today, now));
+    }
+
+    /// The most urgent task in the active context that could be started
+    /// now: open, not waiting, with every blocker done, and in a status that
+    /// may move to in-progress. On a tie the older (lower) id wins.
+    pub fn next_task<'a>(&self, tasks: &'a [Task], today: Date, now: u64) -> Option<&'a Task> {
+        let score = |t: &Task| self.urgency.score(t, tasks, today, now);
+        let context = self.active_context();
+
+        tasks.iter()
+            .filter(|t| context.is_none_or(|context| context.matches(t)))
+            .filter(|t| !t.status.is_closed() && !t.is_waiting(today) && open_blockers(tasks, t).is_empty())
+            .filter(|t| self.storage.workflow().can_transition(&t.status, &TaskStatus::InProgress))
+            .max_by(|a, b| score(a).total_cmp(&score(b)).then(b.id.cmp(&a.id)))
+    }
+
+    fn print_tasks(&self, tasks: &[&Task], tree: bool, context: &RowContext) {
+        if !tree {
+            for task in tasks {
+                self.print_task_row(task, "", context);
+            }
+            return;
+        }
+
+        for (task, depth) in tree_order(tasks) {
+            let prefix = if depth == 0 {
+                String::new()
+            } else {
+                format!("{}└─ ", "   ".repeat(depth - 1))
+            };
+            self.print_task_row(task, &prefix, context);
+        }
+    }
+
+    fn print_task_row(&self, task: &Task, prefix: &str, context: &RowContext) {
+        let workflow = self.storage.workflow();
+        let status_display = format!("{} {}", workflow.emoji(&task.status), task.status.to_string());
+        let color = if task.is_overdue(context.today) {
+            "\x1b[91m".to_string()
+        } else if task.is_due_today(context.today) {
+            "\x1b[95m".to_string()
+        } else {
+            workflow.color(&task.status)
+        };
+        let priority_display = task.priority.map(|p| p.to_string()).unwrap_or_else(|| "-".to_string());
+        let due_display = task.due.map(|due| due.to_string()).unwrap_or_else(|| "-".to_string());
+        let assignee_display = task.assignee.as_deref().unwrap_or("-");
+
+        let age_display = if context.show_age {
+            let age = task.age(context.now).map(datetime::format_age).unwrap_or_else(|| "-".to_string());
+            format!("{:<6} ", age)
+        } else {
+            String::new()
+        };
+
+        let tags_display = if task.tags.is_empty() {
+            String::new()
+        } else {
+            format!(" \x1b[90m{}", self.format_tags(task))
+        };
+        let recur_display = if task.recurrence.is_some() { " 🔁" } else { "" };
+        let wait_display = match task.wait {
+            Some(wait) if task.is_waiting(context.today) => format!(" \x1b[90m⏳{}", wait),
+            _ => String::new(),
+        };
+        let time_spent = task.time_spent(context.now);
+        let time_display = if task.is_tracking() {
+            format!(" \x1b[93m⏱️ {}", datetime::format_duration(time_spent))
+        } else if time_spent > 0 {
+            format!(" \x1b[90m⏱️ {}", datetime::format_duration(time_spent))
+        } else {
+            String::new()
+        };
+        let notes_display = if task.annotations.is_empty() {
+            String::new()
+        } else {
+            format!(" \x1b[90m📎{}", task.annotations.len())
+        };
+
+        println!("{}{:<6} {:<15} {:<10} {:<12} {:<10} {}{}{}{}{}{}{}{}\x1b[0m",
+            color, task.id, status_display, priority_display, due_display, assignee_display, age_display,
+            prefix, task.description, recur_display, wait_display, tags_display, notes_display, time_display);
     }
 
+    /// Clears everything without asking. The CLI goes through `reset_with`.
     pub fn reset(&self) {
-        match self.storage.save_tasks(&vec![]) {
+        self.reset_with(ResetOptions { yes: true, ..ResetOptions::default() });
+    }
+
+    pub fn reset_with(&self, options: ResetOptions) {
+        if options.is_scoped() {
+            self.reset_scoped(options);
+            return;
+        }
+
+        let counts = (
+            self.storage.load_tasks().len(),
+            self.storage.load_archive().len(),
+            self.storage.load_trash().len(),
+        );
+        let summary = format!("{} task(s), {} archived and {} in the trash", counts.0, counts.1, counts.2);
+
+        if options.dry_run {
+            println!("\n\x1b[96m🔍 Dry run: reset would remove {}\x1b[0m\n", summary);
+            return;
+        }
+        if !options.yes && !self.confirm(&format!("This removes {} for good.", summary)) {
+            println!("\n\x1b[93m🐾 Reset cancelled, your tasks are safe!\x1b[0m\n");
+            return;
+        }
+
+        match self.storage.clear() {
             Ok(_) => {
                 println!("\n\x1b[95m🧹 All tasks cleared! Fresh start, bestie!\x1b[0m\n");
             }
             Err(e) => {
-                println!("\n\x1b[91m😿 Failed to reset: {}\x1b[0m\n", e);
+                self.print_save_error(&e);
+            }
+        }
+    }
+
+    // Removes only the tasks matching the status and tag filters.
+    fn reset_scoped(&self, options: ResetOptions) {
+        let status = match &options.status {
+            Some(status_str) => match self.storage.workflow().status(status_str) {
+                Some(status) => Some(status),
+                None => {
+                    println!("\n\x1b[91m😿 Invalid status! Use: {}\x1b[0m\n",
+                        self.storage.workflow().names().join(", "));
+                    return;
+                }
+            },
+            None => None,
+        };
+
+        let mut tasks = self.storage.load_tasks();
+        let removed: Vec<u32> = tasks.iter()
+            .filter(|t| status.as_ref().is_none_or(|status| &t.status == status))
+            .filter(|t| options.tags.iter().all(|tag| t.has_tag(tag)))
+            .map(|t| t.id)
+            .collect();
+
+        if removed.is_empty() {
+            println!("\n\x1b[93m🐾 No tasks match, nothing to reset!\x1b[0m\n");
+            return;
+        }
+
+        if options.dry_run {
+            println!("\n\x1b[96m🔍 Dry run: reset would remove {} task(s):\x1b[0m\n", removed.len());
+            let context = RowContext {
+                today: Date::today(),
+                now: datetime::now(),
+                show_age: false,
+            };
+            for task in tasks.iter().filter(|t| removed.contains(&t.id)) {
+                self.print_task_row(task, "", &context);
+            }
+            println!();
+            return;
+        }
+        if !options.yes && !self.confirm(&format!("This removes {} task(s) for good.", removed.len())) {
+            println!("\n\x1b[93m🐾 Reset cancelled, your tasks are safe!\x1b[0m\n");
+            return;
+        }
+
+        // Subtasks of removed tasks move up to their closest surviving ancestor.
+        let parents: Vec<(u32, Option<u32>)> = tasks.iter().map(|t| (t.id, t.parent)).collect();
+        let parent_of = |id: u32| parents.iter().find(|(task_id, _)| *task_id == id).and_then(|(_, parent)| *parent);
+        for task in tasks.iter_mut() {
+            let mut parent = task.parent;
+            let mut steps = 0;
+            while let Some(id) = parent.filter(|id| removed.contains(id)) {
+                // A parent cycle in a hand-edited file may have no survivor.
+                steps += 1;
+                parent = if steps > parents.len() { None } else { parent_of(id) };
+            }
+            task.parent = parent;
+            task.depends_on.retain(|dep| !removed.contains(dep));
+        }
+        tasks.retain(|t| !removed.contains(&t.id));
+
+        match self.storage.save_tasks(&tasks) {
+            Ok(_) => {
+                println!("\n\x1b[95m🧹 Cleared {} matching task(s)! The rest stay put\x1b[0m\n", removed.len());
+            }
+            Err(e) => {
+                self.print_save_error(&e);
             }
         }
     }
+
+    fn confirm(&self, warning: &str) -> bool {
+        println!("\n\x1b[93m⚠️  {}\x1b[0m", warning);
+        let answer = self.prompt("\x1b[93m   Are you sure? [y/N] \x1b[0m").unwrap_or_default();
+        matches!(answer.to_lowercase().as_str(), "y" | "yes")
+    }
 }
 
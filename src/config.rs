@@ -0,0 +1,569 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use crate::datetime::Date;
+use crate::task::{open_blockers, Priority, Task, TaskStatus, UdaValue};
+
+/// Built-in field names that attributes can't shadow.
+const RESERVED_NAMES: [&str; 13] = [
+    "id", "uuid", "description", "status", "due", "wait",
+    "priority", "tags", "project", "assignee", "parent", "recur",
+    "urgency",
+];
+
+/// Settings read from `~/.trackr_config`.
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    pub workflow: Workflow,
+    /// Who `list --mine` and a bare `trackr assign` refer to.
+    pub user: Option<String>,
+    pub udas: Vec<UdaDef>,
+    pub urgency: Urgency,
+    pub contexts: Vec<ContextDef>,
+}
+
+impl Config {
+    /// Loads the config from `path`, falling back to the defaults if the
+    /// file doesn't exist.
+    pub fn load(path: &str) -> Result<Config, String> {
+        if !Path::new(path).exists() {
+            return Ok(Config::default());
+        }
+
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        Config::parse(&contents).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    pub fn parse(contents: &str) -> Result<Config, String> {
+        enum Section {
+            None,
+            User,
+            Status(usize),
+            Uda(usize),
+            Urgency,
+            Context(usize),
+            Transitions,
+        }
+
+        let mut workflow = Workflow::default();
+        let mut user = None;
+        let mut udas: Vec<UdaDef> = Vec::new();
+        let mut urgency = Urgency::default();
+        let mut contexts: Vec<ContextDef> = Vec::new();
+        let mut section = Section::None;
+
+        for (number, line) in contents.lines().enumerate() {
+            let line = strip_comment(line.trim());
+            let error = |message: String| format!("line {}: {}", number + 1, message);
+
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+
+            if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                let header = header.trim();
+                section = if header == "transitions" {
+                    Section::Transitions
+                } else if header == "user" {
+                    Section::User
+                } else if header == "urgency" {
+                    Section::Urgency
+                } else if let Some(name) = header.strip_prefix("status ") {
+                    let name = name.trim().to_lowercase();
+                    if !Workflow::is_valid_name(&name) {
+                        return Err(error(format!("invalid status name '{}'", name)));
+                    }
+                    Section::Status(workflow.define(&name))
+                } else if let Some(name) = header.strip_prefix("uda ") {
+                    let name = name.trim().to_lowercase();
+                    if !Workflow::is_valid_name(&name) || RESERVED_NAMES.contains(&name.as_str()) {
+                        return Err(error(format!("invalid attribute name '{}'", name)));
+                    }
+                    Section::Uda(UdaDef::define(&mut udas, &name))
+                } else if let Some(name) = header.strip_prefix("context ") {
+                    let name = name.trim().to_lowercase();
+                    if !Workflow::is_valid_name(&name) || name == "none" {
+                        return Err(error(format!("invalid context name '{}'", name)));
+                    }
+                    Section::Context(ContextDef::define(&mut contexts, &name))
+                } else {
+                    return Err(error(format!("unknown section [{}]", header)));
+                };
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(error(format!("expected key = value, got '{}'", line)));
+            };
+            let (key, value) = (key.trim().to_lowercase(), value.trim());
+
+            match section {
+                Section::None => return Err(error(format!("'{}' is outside of any section", key))),
+                Section::User => match key.as_str() {
+                    "name" if Task::is_valid_assignee(value) => user = Some(value.to_string()),
+                    "name" => return Err(error(format!("invalid user name '{}'", value))),
+                    _ => return Err(error(format!("unknown user setting '{}'", key))),
+                },
+                Section::Status(index) => workflow.set(index, &key, value).map_err(error)?,
+                Section::Uda(index) => udas[index].set(&key, value).map_err(error)?,
+                Section::Urgency => urgency.set(&key, value).map_err(error)?,
+                Section::Context(index) => contexts[index].set(&key, value).map_err(error)?,
+                Section::Transitions => {
+                    let targets = value.split(',')
+                        .map(|name| name.trim().to_lowercase())
+                        .filter(|name| !name.is_empty())
+                        .collect();
+                    workflow.transitions.insert(key, targets);
+                }
+            }
+        }
+
+        // Sections can come in any order, so names are checked at the end.
+        for (from, targets) in &workflow.transitions {
+            for name in std::iter::once(from).chain(targets) {
+                if workflow.definition(name).is_none() {
+                    return Err(format!("transitions mention unknown status '{}'", name));
+                }
+            }
+        }
+
+        for def in &udas {
+            if def.kind == UdaType::Enum(Vec::new()) {
+                return Err(format!("attribute '{}' is an enum but has no values", def.name));
+            }
+        }
+
+        for context in &contexts {
+            if context.tags.is_empty() && context.project.is_none() {
+                return Err(format!("context '{}' needs tags or a project", context.name));
+            }
+        }
+
+        Ok(Config { workflow, user, udas, urgency, contexts })
+    }
+}
+
+/// A named filter, switched on with `trackr context set <name>`:
+///
+/// ```text
+/// [context work]
+/// tags = work
+/// project = acme
+/// ```
+///
+/// A task is in the context if it has any of the tags or is in the project.
+/// New tasks get the tags, and the project unless they name their own.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextDef {
+    pub name: String,
+    pub tags: Vec<String>,
+    pub project: Option<String>,
+}
+
+impl ContextDef {
+    fn define(contexts: &mut Vec<ContextDef>, name: &str) -> usize {
+        if let Some(index) = contexts.iter().position(|def| def.name == name) {
+            return index;
+        }
+
+        contexts.push(ContextDef { name: name.to_string(), tags: Vec::new(), project: None });
+        contexts.len() - 1
+    }
+
+    // Applies one `key = value` line of a `[context ...]` section.
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "tags" => {
+                let tags: Vec<String> = value.split(',')
+                    .map(|tag| tag.trim().trim_start_matches('+').to_string())
+                    .filter(|tag| !tag.is_empty())
+                    .collect();
+                if let Some(tag) = tags.iter().find(|tag| !Task::is_valid_tag(tag)) {
+                    return Err(format!("invalid tag '{}'", tag));
+                }
+                self.tags = tags;
+            }
+            "project" if Task::is_valid_project(value) => self.project = Some(value.to_string()),
+            "project" => return Err(format!("invalid project '{}'", value)),
+            _ => return Err(format!("unknown context setting '{}'", key)),
+        }
+        Ok(())
+    }
+
+    pub fn matches(&self, task: &Task) -> bool {
+        self.tags.iter().any(|tag| task.has_tag(tag))
+            || self.project.as_ref().is_some_and(|project| task.in_project(project))
+    }
+}
+
+/// Weights for the urgency score, set under `[urgency]` in the config.
+/// The defaults follow Taskwarrior's.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Urgency {
+    pub in_progress: f64,
+    pub priority_critical: f64,
+    pub priority_high: f64,
+    pub priority_medium: f64,
+    pub priority_low: f64,
+    /// Scaled from 0.2 for tasks due in two weeks or more up to the full
+    /// weight for tasks a week or more overdue.
+    pub due: f64,
+    /// Scaled by age up to a year.
+    pub age: f64,
+    /// For open tasks that other open tasks depend on.
+    pub blocking: f64,
+    /// For tasks waiting on open dependencies; usually negative.
+    pub blocked: f64,
+}
+
+impl Default for Urgency {
+    fn default() -> Urgency {
+        Urgency {
+            in_progress: 4.0,
+            priority_critical: 9.0,
+            priority_high: 6.0,
+            priority_medium: 3.9,
+            priority_low: 1.8,
+            due: 12.0,
+            age: 2.0,
+            blocking: 8.0,
+            blocked: -5.0,
+        }
+    }
+}
+
+impl Urgency {
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let weight = match key {
+            "in_progress" => &mut self.in_progress,
+            "priority_critical" => &mut self.priority_critical,
+            "priority_high" => &mut self.priority_high,
+            "priority_medium" => &mut self.priority_medium,
+            "priority_low" => &mut self.priority_low,
+            "due" => &mut self.due,
+            "age" => &mut self.age,
+            "blocking" => &mut self.blocking,
+            "blocked" => &mut self.blocked,
+            _ => return Err(format!("unknown urgency setting '{}'", key)),
+        };
+        *weight = value.parse::<f64>().ok()
+            .filter(|n| n.is_finite())
+            .ok_or_else(|| format!("urgency weights must be numbers, got '{}'", value))?;
+        Ok(())
+    }
+
+    /// How pressing `task` is; higher is more urgent. `tasks` is the list it
+    /// belongs to, used to find what it blocks and is blocked by. Closed
+    /// tasks score zero.
+    pub fn score(&self, task: &Task, tasks: &[Task], today: Date, now: u64) -> f64 {
+        if task.status.is_closed() {
+            return 0.0;
+        }
+
+        let mut score = 0.0;
+        if task.status == TaskStatus::InProgress {
+            score += self.in_progress;
+        }
+        score += match task.priority {
+            Some(Priority::Critical) => self.priority_critical,
+            Some(Priority::High) => self.priority_high,
+            Some(Priority::Medium) => self.priority_medium,
+            Some(Priority::Low) => self.priority_low,
+            None => 0.0,
+        };
+        if let Some(due) = task.due {
+            let days_overdue = (today.days_since_epoch() - due.days_since_epoch()) as f64;
+            let factor = if days_overdue >= 7.0 {
+                1.0
+            } else if days_overdue >= -14.0 {
+                (days_overdue + 14.0) * 0.8 / 21.0 + 0.2
+            } else {
+                0.2
+            };
+            score += self.due * factor;
+        }
+        if let Some(age) = task.age(now) {
+            score += self.age * (age as f64 / (365.0 * 86_400.0)).min(1.0);
+        }
+        if tasks.iter().any(|t| !t.status.is_closed() && t.depends_on.contains(&task.id)) {
+            score += self.blocking;
+        }
+        if !open_blockers(tasks, task).is_empty() {
+            score += self.blocked;
+        }
+        score
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UdaType {
+    String,
+    Number,
+    Date,
+    /// One of a fixed list of values, which also gives their sort order.
+    Enum(Vec<String>),
+}
+
+/// A user-defined attribute, declared in the config file:
+///
+/// ```text
+/// [uda points]
+/// type = number
+///
+/// [uda severity]
+/// type = enum
+/// values = low, medium, high
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct UdaDef {
+    pub name: String,
+    pub kind: UdaType,
+}
+
+impl UdaDef {
+    fn define(udas: &mut Vec<UdaDef>, name: &str) -> usize {
+        if let Some(index) = udas.iter().position(|def| def.name == name) {
+            return index;
+        }
+
+        udas.push(UdaDef { name: name.to_string(), kind: UdaType::String });
+        udas.len() - 1
+    }
+
+    // Applies one `key = value` line of a `[uda ...]` section.
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "type" => {
+                self.kind = match value.to_lowercase().as_str() {
+                    "string" => UdaType::String,
+                    "number" => UdaType::Number,
+                    "date" => UdaType::Date,
+                    // Keeps values given before the type line.
+                    "enum" => match &self.kind {
+                        UdaType::Enum(values) => UdaType::Enum(values.clone()),
+                        _ => UdaType::Enum(Vec::new()),
+                    },
+                    _ => return Err(format!("unknown attribute type '{}', use string, number, date or enum", value)),
+                };
+            }
+            "values" => {
+                let values: Vec<String> = value.split(',')
+                    .map(|v| v.trim().to_string())
+                    .filter(|v| !v.is_empty())
+                    .collect();
+                if values.iter().any(|v| v.contains('"')) {
+                    return Err(format!("enum values can't contain quotes, got '{}'", value));
+                }
+                self.kind = UdaType::Enum(values);
+            }
+            _ => return Err(format!("unknown attribute setting '{}'", key)),
+        }
+        Ok(())
+    }
+
+    /// Parses a value given on the command line according to the type.
+    pub fn parse_value(&self, raw: &str) -> Result<UdaValue, String> {
+        let raw = raw.trim();
+        match &self.kind {
+            UdaType::String if raw.is_empty() => Err(format!("{} can't be empty", self.name)),
+            UdaType::String => Ok(UdaValue::String(raw.to_string())),
+            UdaType::Number => raw.parse::<f64>().ok()
+                .filter(|n| n.is_finite())
+                .map(UdaValue::Number)
+                .ok_or_else(|| format!("{} must be a number, got '{}'", self.name, raw)),
+            UdaType::Date => Date::parse(raw)
+                .map(UdaValue::Date)
+                .ok_or_else(|| format!("{} must be a date like YYYY-MM-DD, got '{}'", self.name, raw)),
+            UdaType::Enum(values) => values.iter()
+                .find(|value| value.eq_ignore_ascii_case(raw))
+                .map(|value| UdaValue::String(value.clone()))
+                .ok_or_else(|| format!("{} must be one of: {}", self.name, values.join(", "))),
+        }
+    }
+
+    /// Orders two values of this attribute. Enums follow the declared order.
+    pub fn compare(&self, a: &UdaValue, b: &UdaValue) -> Ordering {
+        match (&self.kind, a, b) {
+            (UdaType::Enum(values), UdaValue::String(a), UdaValue::String(b)) => {
+                let rank = |v: &String| values.iter().position(|value| value == v).unwrap_or(values.len());
+                rank(a).cmp(&rank(b))
+            }
+            _ => a.compare(b),
+        }
+    }
+}
+
+/// How a status looks and whether tasks in it count as finished.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusDef {
+    pub name: String,
+    pub emoji: String,
+    /// ANSI colour code, e.g. `93`.
+    pub color: u8,
+    pub closed: bool,
+}
+
+/// The set of statuses a task can be in and the moves allowed between them.
+///
+/// Read from an INI-style file:
+///
+/// ```text
+/// [status cancelled]
+/// emoji = 🚫
+/// color = gray
+/// closed = true
+///
+/// [transitions]
+/// todo = in-progress, cancelled
+/// in-progress = done, todo
+/// ```
+///
+/// Statuses without a `[transitions]` entry may move anywhere.
+#[derive(Debug, Clone)]
+pub struct Workflow {
+    statuses: Vec<StatusDef>,
+    transitions: BTreeMap<String, Vec<String>>,
+}
+
+impl Default for Workflow {
+    fn default() -> Workflow {
+        let builtin = |status: TaskStatus, color: u8| StatusDef {
+            name: status.to_string(),
+            emoji: status.emoji().to_string(),
+            color,
+            closed: status.is_closed(),
+        };
+
+        Workflow {
+            statuses: vec![
+                builtin(TaskStatus::Todo, 97),
+                builtin(TaskStatus::InProgress, 93),
+                builtin(TaskStatus::Done, 92),
+            ],
+            transitions: BTreeMap::new(),
+        }
+    }
+}
+
+impl Workflow {
+    /// Parses just the workflow part of a config file.
+    pub fn parse(contents: &str) -> Result<Workflow, String> {
+        Config::parse(contents).map(|config| config.workflow)
+    }
+
+    // Applies one `key = value` line of a `[status ...]` section.
+    fn set(&mut self, index: usize, key: &str, value: &str) -> Result<(), String> {
+        let builtin = TaskStatus::from_str(&self.statuses[index].name).is_some();
+        let def = &mut self.statuses[index];
+        match key {
+            "emoji" => def.emoji = value.to_string(),
+            "color" => {
+                def.color = Workflow::parse_color(value)
+                    .ok_or_else(|| format!("unknown color '{}'", value))?;
+            }
+            "closed" => {
+                let closed = match value {
+                    "true" | "yes" => true,
+                    "false" | "no" => false,
+                    _ => return Err(format!("closed must be true or false, got '{}'", value)),
+                };
+                if builtin && closed != def.closed {
+                    return Err(format!("'closed' can't be changed for built-in status '{}'", def.name));
+                }
+                def.closed = closed;
+            }
+            _ => return Err(format!("unknown status setting '{}'", key)),
+        }
+        Ok(())
+    }
+
+    fn define(&mut self, name: &str) -> usize {
+        if let Some(index) = self.statuses.iter().position(|def| def.name == name) {
+            return index;
+        }
+
+        self.statuses.push(StatusDef {
+            name: name.to_string(),
+            emoji: "🔹".to_string(),
+            color: 97,
+            closed: false,
+        });
+        self.statuses.len() - 1
+    }
+
+    fn is_valid_name(name: &str) -> bool {
+        !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    }
+
+    fn parse_color(value: &str) -> Option<u8> {
+        match value.to_lowercase().as_str() {
+            "gray" | "grey" => Some(90),
+            "red" => Some(91),
+            "green" => Some(92),
+            "yellow" => Some(93),
+            "blue" => Some(94),
+            "magenta" => Some(95),
+            "cyan" => Some(96),
+            "white" => Some(97),
+            code => code.parse().ok().filter(|c| matches!(c, 30..=37 | 90..=97)),
+        }
+    }
+
+    fn definition(&self, name: &str) -> Option<&StatusDef> {
+        self.statuses.iter().find(|def| def.name == name)
+    }
+
+    /// Looks up a status by name, built-in or configured.
+    pub fn status(&self, name: &str) -> Option<TaskStatus> {
+        let name = name.to_lowercase();
+        if let Some(status) = TaskStatus::from_str(&name) {
+            return Some(status);
+        }
+
+        self.definition(&name).map(|def| TaskStatus::Custom {
+            name: def.name.clone(),
+            closed: def.closed,
+        })
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.statuses.iter().map(|def| def.name.as_str()).collect()
+    }
+
+    pub fn emoji<'a>(&'a self, status: &'a TaskStatus) -> &'a str {
+        match self.definition(&status.to_string()) {
+            Some(def) => &def.emoji,
+            None => status.emoji(),
+        }
+    }
+
+    /// The escape sequence that switches to the status colour.
+    pub fn color(&self, status: &TaskStatus) -> String {
+        let code = self.definition(&status.to_string()).map(|def| def.color).unwrap_or(97);
+        format!("\x1b[{}m", code)
+    }
+
+    /// Statuses reachable from `from`, or `None` if any move is allowed.
+    pub fn allowed_from(&self, from: &TaskStatus) -> Option<&[String]> {
+        self.transitions.get(&from.to_string()).map(|targets| targets.as_slice())
+    }
+
+    pub fn can_transition(&self, from: &TaskStatus, to: &TaskStatus) -> bool {
+        from == to || self.allowed_from(from).is_none_or(|targets| targets.contains(&to.to_string()))
+    }
+}
+
+// Drops a trailing `# comment`. The `#` has to stand on its own, so a value
+// such as the #️⃣ emoji is left alone.
+fn strip_comment(line: &str) -> &str {
+    for (i, _) in line.match_indices('#') {
+        let starts_word = line[..i].chars().next_back().is_none_or(char::is_whitespace);
+        let ends_word = line[i + 1..].chars().next().is_none_or(char::is_whitespace);
+        if starts_word && ends_word {
+            return line[..i].trim_end();
+        }
+    }
+    line
+}
@@ -0,0 +1,254 @@
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Current time as seconds since the Unix epoch (UTC).
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Formats a timestamp as `YYYY-MM-DDTHH:MM:SSZ`.
+pub fn format_timestamp(secs: u64) -> String {
+    let (year, month, day) = civil_from_days((secs / SECONDS_PER_DAY) as i64);
+    let rem = secs % SECONDS_PER_DAY;
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year, month, day, rem / 3600, (rem % 3600) / 60, rem % 60)
+}
+
+/// Parses a timestamp written by `format_timestamp`.
+pub fn parse_timestamp(s: &str) -> Option<u64> {
+    let s = s.trim().strip_suffix('Z')?;
+    let (date, time) = s.split_once('T')?;
+
+    let mut date_parts = date.split('-');
+    let year: i64 = date_parts.next()?.parse().ok()?;
+    let month: u32 = date_parts.next()?.parse().ok()?;
+    let day: u32 = date_parts.next()?.parse().ok()?;
+    if date_parts.next().is_some() || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let mut time_parts = time.split(':');
+    let hour: u64 = time_parts.next()?.parse().ok()?;
+    let minute: u64 = time_parts.next()?.parse().ok()?;
+    let second: u64 = time_parts.next()?.parse().ok()?;
+    if time_parts.next().is_some() || hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+
+    let days = days_from_civil(year, month, day);
+    if days < 0 {
+        return None;
+    }
+
+    Some(days as u64 * SECONDS_PER_DAY + hour * 3600 + minute * 60 + second)
+}
+
+/// Short human-readable duration such as `3d`, `5h`, `12m` or `now`.
+pub fn format_age(secs: u64) -> String {
+    if secs >= SECONDS_PER_DAY {
+        format!("{}d", secs / SECONDS_PER_DAY)
+    } else if secs >= 3600 {
+        format!("{}h", secs / 3600)
+    } else if secs >= 60 {
+        format!("{}m", secs / 60)
+    } else {
+        "now".to_string()
+    }
+}
+
+/// How long ago something happened, like `3h ago` or `just now`.
+pub fn format_ago(secs: u64) -> String {
+    match format_age(secs).as_str() {
+        "now" => "just now".to_string(),
+        age => format!("{} ago", age),
+    }
+}
+
+/// Formats tracked time like `2h 05m`, `12m` or `40s`.
+pub fn format_duration(secs: u64) -> String {
+    if secs >= 3600 {
+        format!("{}h {:02}m", secs / 3600, secs % 3600 / 60)
+    } else if secs >= 60 {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+/// Parses a span like `30d`, `2w` or `12h` into seconds.
+pub fn parse_duration(s: &str) -> Option<u64> {
+    let unit = match s.chars().last()? {
+        'h' => 3600,
+        'd' => SECONDS_PER_DAY,
+        'w' => 7 * SECONDS_PER_DAY,
+        _ => return None,
+    };
+    let amount: u64 = s[..s.len() - 1].parse().ok()?;
+    amount.checked_mul(unit)
+}
+
+/// A calendar day, ordered chronologically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    pub fn new(year: i64, month: u32, day: u32) -> Option<Date> {
+        let date = Date { year, month, day };
+        if (1..=12).contains(&month) && Date::from_days(date.days_since_epoch()) == date {
+            Some(date)
+        } else {
+            None
+        }
+    }
+
+    /// Today in the local time zone. Due and wait dates are the user's
+    /// days, so this is what they're compared against.
+    pub fn today() -> Date {
+        Date::from_local_timestamp(now())
+    }
+
+    /// The UTC date of a timestamp.
+    pub fn from_timestamp(secs: u64) -> Date {
+        Date::from_days((secs / SECONDS_PER_DAY) as i64)
+    }
+
+    /// The local date of a timestamp, daylight saving included.
+    ///
+    /// Only 64-bit Unix targets ask the C library, since that's where
+    /// `time_t` is reliably a 64-bit integer and `struct tm` has the layout
+    /// below. Elsewhere this is the UTC date.
+    #[cfg(all(unix, target_pointer_width = "64"))]
+    pub fn from_local_timestamp(secs: u64) -> Date {
+        use std::os::raw::{c_char, c_int, c_long};
+
+        // `struct tm` as glibc, musl and the BSDs lay it out on 64-bit targets.
+        #[repr(C)]
+        struct Tm {
+            sec: c_int,
+            min: c_int,
+            hour: c_int,
+            mday: c_int,
+            mon: c_int,
+            year: c_int,
+            wday: c_int,
+            yday: c_int,
+            isdst: c_int,
+            gmtoff: c_long,
+            zone: *const c_char,
+        }
+
+        unsafe extern "C" {
+            fn tzset();
+            fn localtime_r(time: *const i64, result: *mut Tm) -> *mut Tm;
+        }
+
+        let Ok(time) = i64::try_from(secs) else {
+            return Date::from_timestamp(secs);
+        };
+        // SAFETY: `Tm` matches the C layout, a zeroed one is a valid value,
+        // and `localtime_r` only writes through the pointers it is given.
+        let local = unsafe {
+            let mut tm: Tm = std::mem::zeroed();
+            tzset();
+            (!localtime_r(&time, &mut tm).is_null()).then_some(tm)
+        };
+
+        match local {
+            Some(tm) => Date { year: tm.year as i64 + 1900, month: tm.mon as u32 + 1, day: tm.mday as u32 },
+            None => Date::from_timestamp(secs),
+        }
+    }
+
+    #[cfg(not(all(unix, target_pointer_width = "64")))]
+    pub fn from_local_timestamp(secs: u64) -> Date {
+        Date::from_timestamp(secs)
+    }
+
+    pub fn from_days(days: i64) -> Date {
+        let (year, month, day) = civil_from_days(days);
+        Date { year, month, day }
+    }
+
+    pub fn days_since_epoch(&self) -> i64 {
+        days_from_civil(self.year, self.month, self.day)
+    }
+
+    pub fn add_days(&self, days: i64) -> Date {
+        Date::from_days(self.days_since_epoch() + days)
+    }
+
+    /// Day of the week, 0 = Monday through 6 = Sunday.
+    pub fn weekday(&self) -> u32 {
+        // 1970-01-01 was a Thursday.
+        (self.days_since_epoch() + 3).rem_euclid(7) as u32
+    }
+
+    /// Same day `months` later, clamped to the end of shorter months.
+    pub fn add_months(&self, months: u32) -> Date {
+        self.add_months_on(months, self.day)
+    }
+
+    /// Day `day` of the month `months` later, clamped to the end of shorter
+    /// months.
+    pub fn add_months_on(&self, months: u32, day: u32) -> Date {
+        let total = self.year * 12 + (self.month as i64 - 1) + months as i64;
+        let year = total.div_euclid(12);
+        let month = total.rem_euclid(12) as u32 + 1;
+        let mut day = day.clamp(1, 31);
+        while Date::new(year, month, day).is_none() {
+            day -= 1;
+        }
+        Date { year, month, day }
+    }
+
+    /// Parses `YYYY-MM-DD`.
+    pub fn parse(s: &str) -> Option<Date> {
+        let mut parts = s.trim().split('-');
+        let year = parts.next()?.parse().ok()?;
+        let month = parts.next()?.parse().ok()?;
+        let day = parts.next()?.parse().ok()?;
+        if parts.next().is_some() {
+            return None;
+        }
+        Date::new(year, month, day)
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+// Howard Hinnant's days-from-civil algorithm, proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let m = month as i64;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = if month <= 2 { yoe + era * 400 + 1 } else { yoe + era * 400 };
+    (year, month, day)
+}
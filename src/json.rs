@@ -0,0 +1,412 @@
+use std::fmt;
+use std::ops::Range;
+
+/// Nesting deeper than this is rejected rather than risking the stack.
+const MAX_DEPTH: usize = 256;
+
+/// Where a value starts in the input. Lines and columns count from 1, and
+/// columns count characters rather than bytes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+    pub offset: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonError {
+    pub position: Position,
+    pub message: String,
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}: {}", self.position.line, self.position.column, self.message)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    /// Members in the order they were written.
+    Object(Vec<(String, Json)>),
+}
+
+/// A parsed value together with where it sits in the input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Json {
+    pub value: Value,
+    pub position: Position,
+    /// Byte offset just past the end of the value.
+    pub end: usize,
+}
+
+impl Json {
+    /// The member called `key`, if this is an object that has one.
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match &self.value {
+            Value::Object(members) => members.iter().find(|(name, _)| name == key).map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        self.value == Value::Null
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match &self.value {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self.value {
+            Value::Number(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    /// The value as a whole number that fits in a `u32`.
+    pub fn as_u32(&self) -> Option<u32> {
+        self.as_f64()
+            .filter(|n| n.fract() == 0.0 && (0.0..=u32::MAX as f64).contains(n))
+            .map(|n| n as u32)
+    }
+
+    /// The value as a whole number that fits in a `u64`.
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_f64()
+            .filter(|n| n.fract() == 0.0 && (0.0..=u64::MAX as f64).contains(n))
+            .map(|n| n as u64)
+    }
+
+    pub fn as_array(&self) -> Option<&[Json]> {
+        match &self.value {
+            Value::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&[(String, Json)]> {
+        match &self.value {
+            Value::Object(members) => Some(members),
+            _ => None,
+        }
+    }
+
+    /// The byte range the value covers in the input it was parsed from.
+    pub fn span(&self) -> Range<usize> {
+        self.position.offset..self.end
+    }
+
+    /// An error pointing at this value.
+    pub fn error(&self, message: String) -> JsonError {
+        JsonError { position: self.position, message }
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        match self.value {
+            Value::Null => "null",
+            Value::Bool(_) => "a boolean",
+            Value::Number(_) => "a number",
+            Value::String(_) => "a string",
+            Value::Array(_) => "an array",
+            Value::Object(_) => "an object",
+        }
+    }
+}
+
+/// Parses a complete JSON document (RFC 8259). Anything but whitespace after
+/// the value is an error.
+pub fn parse(input: &str) -> Result<Json, JsonError> {
+    let mut parser = Parser {
+        input,
+        bytes: input.as_bytes(),
+        offset: 0,
+        line: 1,
+        column: 1,
+    };
+
+    parser.skip_whitespace();
+    let value = parser.parse_value(0)?;
+    parser.skip_whitespace();
+    if parser.peek().is_some() {
+        return Err(parser.error("unexpected content after the end of the document"));
+    }
+    Ok(value)
+}
+
+// Works on bytes, since everything JSON gives meaning to is ASCII. Other
+// characters only appear inside strings, which are copied a run at a time.
+struct Parser<'a> {
+    input: &'a str,
+    bytes: &'a [u8],
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn position(&self) -> Position {
+        Position { line: self.line, column: self.column, offset: self.offset }
+    }
+
+    fn error(&self, message: &str) -> JsonError {
+        JsonError { position: self.position(), message: message.to_string() }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.offset).copied()
+    }
+
+    // Moves past one byte. Continuation bytes of a multi-byte character don't
+    // start a new column.
+    fn next(&mut self) -> Option<u8> {
+        let b = self.peek()?;
+        self.offset += 1;
+        if b == b'\n' {
+            self.line += 1;
+            self.column = 1;
+        } else if b & 0xC0 != 0x80 {
+            self.column += 1;
+        }
+        Some(b)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.next();
+        }
+    }
+
+    fn expect(&mut self, expected: u8, message: &str) -> Result<(), JsonError> {
+        if self.peek() == Some(expected) {
+            self.next();
+            Ok(())
+        } else {
+            Err(self.error(message))
+        }
+    }
+
+    fn parse_value(&mut self, depth: usize) -> Result<Json, JsonError> {
+        let position = self.position();
+        let value = match self.peek() {
+            Some(b'{') => self.parse_object(depth)?,
+            Some(b'[') => self.parse_array(depth)?,
+            Some(b'"') => Value::String(self.parse_string()?),
+            Some(b'-' | b'0'..=b'9') => Value::Number(self.parse_number()?),
+            Some(b't') => self.parse_literal("true", Value::Bool(true))?,
+            Some(b'f') => self.parse_literal("false", Value::Bool(false))?,
+            Some(b'n') => self.parse_literal("null", Value::Null)?,
+            Some(_) => {
+                let c = self.input[self.offset..].chars().next().unwrap_or('\u{FFFD}');
+                return Err(self.error(&format!("unexpected character '{}'", c)));
+            }
+            None => return Err(self.error("unexpected end of input")),
+        };
+        Ok(Json { value, position, end: self.offset })
+    }
+
+    fn parse_literal(&mut self, word: &str, value: Value) -> Result<Value, JsonError> {
+        if !self.input[self.offset..].starts_with(word) {
+            return Err(self.error("expected a value"));
+        }
+        for _ in word.bytes() {
+            self.next();
+        }
+        Ok(value)
+    }
+
+    fn parse_object(&mut self, depth: usize) -> Result<Value, JsonError> {
+        if depth >= MAX_DEPTH {
+            return Err(self.error("nested too deeply"));
+        }
+        self.next();
+        self.skip_whitespace();
+
+        let mut members = Vec::new();
+        if self.peek() == Some(b'}') {
+            self.next();
+            return Ok(Value::Object(members));
+        }
+
+        loop {
+            if self.peek() != Some(b'"') {
+                return Err(self.error("expected a string key"));
+            }
+            let key = self.parse_string()?;
+            self.skip_whitespace();
+            self.expect(b':', "expected ':' after the key")?;
+            self.skip_whitespace();
+            let value = self.parse_value(depth + 1)?;
+            members.push((key, value));
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => {
+                    self.next();
+                    self.skip_whitespace();
+                }
+                Some(b'}') => {
+                    self.next();
+                    return Ok(Value::Object(members));
+                }
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn parse_array(&mut self, depth: usize) -> Result<Value, JsonError> {
+        if depth >= MAX_DEPTH {
+            return Err(self.error("nested too deeply"));
+        }
+        self.next();
+        self.skip_whitespace();
+
+        let mut items = Vec::new();
+        if self.peek() == Some(b']') {
+            self.next();
+            return Ok(Value::Array(items));
+        }
+
+        loop {
+            items.push(self.parse_value(depth + 1)?);
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => {
+                    self.next();
+                    self.skip_whitespace();
+                }
+                Some(b']') => {
+                    self.next();
+                    return Ok(Value::Array(items));
+                }
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, JsonError> {
+        self.next();
+
+        let mut result = String::new();
+        loop {
+            // Plain characters up to the next quote, escape or control
+            // character are copied as one slice.
+            let start = self.offset;
+            while let Some(b) = self.peek()
+                && b != b'"' && b != b'\\' && b >= 0x20
+            {
+                self.next();
+            }
+            result.push_str(&self.input[start..self.offset]);
+
+            let position = self.position();
+            match self.next() {
+                None => return Err(self.error("unterminated string")),
+                Some(b'"') => return Ok(result),
+                Some(b'\\') => match self.next() {
+                    Some(b'"') => result.push('"'),
+                    Some(b'\\') => result.push('\\'),
+                    Some(b'/') => result.push('/'),
+                    Some(b'b') => result.push('\u{8}'),
+                    Some(b'f') => result.push('\u{c}'),
+                    Some(b'n') => result.push('\n'),
+                    Some(b'r') => result.push('\r'),
+                    Some(b't') => result.push('\t'),
+                    Some(b'u') => result.push(self.parse_unicode_escape()?),
+                    _ => return Err(JsonError { position, message: "invalid escape sequence".to_string() }),
+                },
+                Some(_) => {
+                    return Err(JsonError { position, message: "control character in string".to_string() });
+                }
+            }
+        }
+    }
+
+    // Reads the hex digits after `\u`, joining surrogate pairs. A lone
+    // surrogate is valid JSON but not a character, so it becomes U+FFFD.
+    fn parse_unicode_escape(&mut self) -> Result<char, JsonError> {
+        let high = self.parse_hex4()?;
+        if !(0xD800..0xDC00).contains(&high) {
+            return Ok(char::from_u32(high).unwrap_or('\u{FFFD}'));
+        }
+
+        if !self.input[self.offset..].starts_with("\\u") {
+            return Ok('\u{FFFD}');
+        }
+        let checkpoint = (self.offset, self.line, self.column);
+        self.next();
+        self.next();
+        let low = self.parse_hex4()?;
+        if !(0xDC00..0xE000).contains(&low) {
+            // Not a pair; leave the second escape to be read on its own.
+            (self.offset, self.line, self.column) = checkpoint;
+            return Ok('\u{FFFD}');
+        }
+        Ok(char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)).unwrap_or('\u{FFFD}'))
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32, JsonError> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let digit = self.peek()
+                .and_then(|b| (b as char).to_digit(16))
+                .ok_or_else(|| self.error("expected four hex digits after \\u"))?;
+            self.next();
+            code = code * 16 + digit;
+        }
+        Ok(code)
+    }
+
+    fn parse_number(&mut self) -> Result<f64, JsonError> {
+        let start = self.offset;
+        let position = self.position();
+
+        if self.peek() == Some(b'-') {
+            self.next();
+        }
+        match self.peek() {
+            Some(b'0') => {
+                self.next();
+            }
+            Some(b'1'..=b'9') => self.skip_digits(),
+            _ => return Err(self.error("expected a digit")),
+        }
+        if self.peek() == Some(b'.') {
+            self.next();
+            if !matches!(self.peek(), Some(b'0'..=b'9')) {
+                return Err(self.error("expected a digit after '.'"));
+            }
+            self.skip_digits();
+        }
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            self.next();
+            if matches!(self.peek(), Some(b'+' | b'-')) {
+                self.next();
+            }
+            if !matches!(self.peek(), Some(b'0'..=b'9')) {
+                return Err(self.error("expected a digit in the exponent"));
+            }
+            self.skip_digits();
+        }
+
+        self.input[start..self.offset].parse::<f64>()
+            .ok()
+            .filter(|n| n.is_finite())
+            .ok_or(JsonError { position, message: "number out of range".to_string() })
+    }
+
+    fn skip_digits(&mut self) {
+        while matches!(self.peek(), Some(b'0'..=b'9')) {
+            self.next();
+        }
+    }
+}
@@ -2,4 +2,7 @@ pub mod task;
 pub mod storage;
 pub mod commands;
 pub mod quotes;
-
+pub mod datetime;
+pub mod config;
+pub mod json;
+pub mod state;
@@ -1,44 +1,126 @@
-mod task;
-mod storage;
-mod commands;
-mod quotes;
-
 use std::env;
-use storage::Storage;
-use commands::Commands;
+use trackr::config::Config;
+use trackr::datetime;
+use trackr::storage::Storage;
+use trackr::state::State;
+use trackr::commands::{ChildAction, Commands, ListOptions, ResetOptions, TaskOptions};
 
 fn print_banner() {
     println!("\x1b[95m");
-    println!("");
+    println!();
     println!("           /\\_/\\  ");
     println!("          ( o.o ) ");
     println!("           > ^ <  ");
-    println!("");
+    println!();
     println!("    ████████╗██████╗  █████╗  ██████╗██╗  ██╗██████╗ ");
     println!("    ╚══██╔══╝██╔══██╗██╔══██╗██╔════╝██║ ██╔╝██╔══██╗");
     println!("       ██║   ██████╔╝███████║██║     █████╔╝ ██████╔╝");
     println!("       ██║   ██╔══██╗██╔══██║██║     ██╔═██╗ ██╔══██╗");
     println!("       ██║   ██║  ██║██║  ██║╚██████╗██║  ██╗██║  ██║");
     println!("       ╚═╝   ╚═╝  ╚═╝╚═╝  ╚═╝ ╚═════╝╚═╝  ╚═╝╚═╝  ╚═╝");
-    println!("");
+    println!();
     println!("              \x1b[96mstay pawsitive 😸🐾\x1b[0m");
     println!();
 }
 
 fn print_help() {
     println!("\x1b[96m📝 How to use trackr:\x1b[0m\n");
-    println!("\x1b[93m  trackr add <description>\x1b[0m");
-    println!("    Add a new task\n");
-    println!("\x1b[93m  trackr update <id> <new_description>\x1b[0m");
-    println!("    Update an existing task\n");
-    println!("\x1b[93m  trackr delete <id>\x1b[0m");
-    println!("    Delete a task\n");
+    println!("\x1b[93m  trackr add <description> [+tag...] [--due YYYY-MM-DD] [--priority <level>]\x1b[0m");
+    println!("\x1b[93m             [--project <name>] [--parent <id>] [--recur <rule>] [--wait YYYY-MM-DD]\x1b[0m");
+    println!("\x1b[93m             [--set <attr>=<value>]\x1b[0m");
+    println!("    Add a new task (--wait hides it from the list until that day)\n");
+    println!("\x1b[93m  trackr update <id> [new_description] [+tag...] [--due YYYY-MM-DD|none]\x1b[0m");
+    println!("\x1b[93m                [--priority <level>|none] [--project <name>|none] [--parent <id>|none]\x1b[0m");
+    println!("\x1b[93m                [--recur <rule>|none] [--wait YYYY-MM-DD|none] [--set <attr>=<value>|none]\x1b[0m");
+    println!("    Update an existing task");
+    println!("    Recurrence rules: daily, weekly, weekly:mon,thu, monthly, monthly:N (day), every:N (days)");
+    println!("    Attributes for --set are declared as [uda <name>] in ~/.trackr_config\n");
+    println!("\x1b[93m  trackr start <id>\x1b[0m");
+    println!("    Start a timer on a task and move it to in-progress (stops any other timer)\n");
+    println!("\x1b[93m  trackr stop [id]\x1b[0m");
+    println!("    Stop the running timer\n");
+    println!("\x1b[93m  trackr prio <id> <level>\x1b[0m");
+    println!("    Set task priority (low, medium, high, critical, none)\n");
+    println!("\x1b[93m  trackr assign <id> [name|none]\x1b[0m");
+    println!("    Assign a task to someone (yourself if no name is given)\n");
+    println!("\x1b[93m  trackr depend <id> <on-id>\x1b[0m");
+    println!("    Mark a task as blocked by another task\n");
+    println!("\x1b[93m  trackr undepend <id> <on-id>\x1b[0m");
+    println!("    Remove a dependency\n");
+    println!("\x1b[93m  trackr tag <id> +tag -tag...\x1b[0m");
+    println!("    Add or remove tags on a task\n");
+    println!("\x1b[93m  trackr tags\x1b[0m");
+    println!("    List every tag with its task count\n");
+    println!("\x1b[93m  trackr show <id>\x1b[0m");
+    println!("    Show every detail of a task, including its notes\n");
+    println!("\x1b[93m  trackr annotate <id> <text>\x1b[0m");
+    println!("    Add a timestamped note to a task\n");
+    println!("\x1b[93m  trackr denotate <id> <n>\x1b[0m");
+    println!("    Remove note number n from a task\n");
+    println!("\x1b[93m  trackr context [set <name>|none]\x1b[0m");
+    println!("    Show contexts, or switch one on so list, next, tags and projects only show its tasks");
+    println!("    and new tasks get its tags and project\n");
+    println!("\x1b[93m  trackr recover\x1b[0m");
+    println!("    Back up a broken task file and keep every task that can still be read\n");
+    println!("\x1b[93m  trackr next\x1b[0m");
+    println!("    Show the most urgent task you can work on now (weights under [urgency] in ~/.trackr_config)\n");
+    println!("\x1b[93m  trackr projects\x1b[0m");
+    println!("    Show todo/in-progress/done counts per project\n");
+    println!("\x1b[93m  trackr delete <id> [--children remove|reparent]\x1b[0m");
+    println!("    Move a task to the trash (asks what to do with its subtasks unless --children is given)\n");
+    println!("\x1b[93m  trackr trash [--empty [--older-than 30d]]\x1b[0m");
+    println!("    List deleted tasks, or purge them for good\n");
+    println!("\x1b[93m  trackr restore <id>\x1b[0m");
+    println!("    Bring a deleted task (and its deleted subtasks) back\n");
     println!("\x1b[93m  trackr mark <id> <status>\x1b[0m");
-    println!("    Mark task status (todo, in-progress, done)\n");
-    println!("\x1b[93m  trackr list [status]\x1b[0m");
-    println!("    List all tasks or filter by status\n");
-    println!("\x1b[93m  trackr reset\x1b[0m");
-    println!("    Clear all tasks\n");
+    println!("    Mark task status (todo, in-progress, done, or any status from ~/.trackr_config)\n");
+    println!("\x1b[93m  trackr list [status] [--age] [--overdue] [--due-before YYYY-MM-DD]\x1b[0m");
+    println!("\x1b[93m              [--priority <level>] [--tag <tag>] [--project <prefix>] [--group]\x1b[0m");
+    println!("\x1b[93m              [--sort id|priority|urgency|<attr>] [--tree] [--ready] [--archived] [--all]\x1b[0m");
+    println!("\x1b[93m              [--mine] [--assignee <name>] [--where <attr>=<value>]\x1b[0m");
+    println!("    List all tasks or filter them (--age shows how old each task is, --group groups by project,");
+    println!("    --tree draws subtasks under their parents, --ready shows todo tasks with no open blockers,");
+    println!("    --archived lists the archive instead, --all includes tasks still waiting)\n");
+    println!("\x1b[93m  trackr archive <id>|--done\x1b[0m");
+    println!("    Move a finished task (or every finished task) out of the main list\n");
+    println!("\x1b[93m  trackr unarchive <id>\x1b[0m");
+    println!("    Bring an archived task back\n");
+    println!("\x1b[93m  trackr reset [--status <status>] [--tag <tag>] [--dry-run] [--yes]\x1b[0m");
+    println!("    Clear all tasks, or only the matching ones (asks first unless --yes is given,");
+    println!("    --dry-run only shows what would go)\n");
+    println!("\x1b[90m  Anywhere an <id> is expected you can also pass a unique UUID prefix (see trackr show).\x1b[0m\n");
+}
+
+fn flag_value<'a>(iter: &mut impl Iterator<Item = &'a String>, flag: &str) -> Option<String> {
+    match iter.next() {
+        Some(value) => Some(value.clone()),
+        None => {
+            println!("\x1b[91m😿 Error: Missing value for {}!\x1b[0m\n", flag);
+            None
+        }
+    }
+}
+
+fn parse_task_args(args: &[String]) -> Option<(String, TaskOptions)> {
+    let mut words = Vec::new();
+    let mut options = TaskOptions::default();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--due" => options.due = Some(flag_value(&mut iter, arg)?),
+            "--wait" => options.wait = Some(flag_value(&mut iter, arg)?),
+            "--priority" => options.priority = Some(flag_value(&mut iter, arg)?),
+            "--project" => options.project = Some(flag_value(&mut iter, arg)?),
+            "--parent" => options.parent = Some(flag_value(&mut iter, arg)?),
+            "--recur" => options.recur = Some(flag_value(&mut iter, arg)?),
+            "--set" => options.set.push(flag_value(&mut iter, arg)?),
+            _ if arg.len() > 1 && arg.starts_with('+') => options.tags.push(arg[1..].to_string()),
+            _ => words.push(arg.clone()),
+        }
+    }
+
+    Some((words.join(" "), options))
 }
 
 fn main() {
@@ -53,11 +135,33 @@ fn main() {
 
     let home_dir = env::var("HOME").unwrap_or_else(|_| ".".to_string());
     let tasks_path = format!("{}/.trackr_tasks.json", home_dir);
-    let storage = Storage::new(&tasks_path);
-    let commands = Commands::new(storage);
-
+    let config = match Config::load(&format!("{}/.trackr_config", home_dir)) {
+        Ok(config) => config,
+        Err(e) => {
+            println!("\x1b[91m😿 Error: Invalid config, {}\x1b[0m\n", e);
+            return;
+        }
+    };
+    let user = config.user.or_else(|| env::var("USER").ok().filter(|user| !user.is_empty()));
+    let storage = Storage::with_workflow(&tasks_path, config.workflow).with_udas(config.udas);
     let command = &args[1];
 
+    // Nothing runs against a file that couldn't be read, except recovering
+    // it and the commands that never open it.
+    if !matches!(command.as_str(), "recover" | "context" | "help" | "--help" | "-h")
+        && let Err(e) = storage.check()
+    {
+        println!("\x1b[91m😿 Error: Can't read your tasks, {}\x1b[0m", e);
+        println!("\x1b[93m   Nothing was changed. Fix the file by hand, or run trackr recover to back it up\x1b[0m");
+        println!("\x1b[93m   and keep every task that's still readable\x1b[0m\n");
+        return;
+    }
+    let commands = Commands::new(storage)
+        .with_user(user)
+        .with_urgency(config.urgency)
+        .with_contexts(config.contexts)
+        .with_state(State::new(&format!("{}/.trackr_state", home_dir)));
+
     match command.as_str() {
         "add" => {
             if args.len() < 3 {
@@ -65,35 +169,62 @@ fn main() {
                 println!("\x1b[93m   Usage: trackr add <description>\x1b[0m\n");
                 return;
             }
-            let description = args[2..].join(" ");
-            commands.add(description);
+            let Some((description, options)) = parse_task_args(&args[2..]) else {
+                return;
+            };
+            if description.is_empty() {
+                println!("\x1b[91m😿 Error: Please provide a task description!\x1b[0m\n");
+                return;
+            }
+            commands.add_with_options(description, options);
         }
         "update" => {
             if args.len() < 4 {
                 println!("\x1b[91m😿 Error: Please provide task ID and new description!\x1b[0m");
-                println!("\x1b[93m   Usage: trackr update <id> <new_description>\x1b[0m\n");
+                println!("\x1b[93m   Usage: trackr update <id> [new_description] [--due YYYY-MM-DD|none] [--priority <level>|none]\x1b[0m\n");
                 return;
             }
-            match args[2].parse::<u32>() {
+            match commands.resolve_id(&args[2]) {
                 Ok(id) => {
-                    let new_description = args[3..].join(" ");
-                    commands.update(id, new_description);
+                    let Some((new_description, options)) = parse_task_args(&args[3..]) else {
+                        return;
+                    };
+                    if new_description.is_empty() && options.is_empty() {
+                        println!("\x1b[91m😿 Error: Nothing to update!\x1b[0m\n");
+                        return;
+                    }
+                    let new_description = Some(new_description).filter(|d| !d.is_empty());
+                    commands.update_with_options(id, new_description, options);
                 }
-                Err(_) => {
-                    println!("\x1b[91m😿 Error: Invalid task ID! Must be a number.\x1b[0m\n");
+                Err(e) => {
+                    println!("\x1b[91m😿 Error: {}\x1b[0m\n", e);
                 }
             }
         }
         "delete" => {
             if args.len() < 3 {
                 println!("\x1b[91m😿 Error: Please provide a task ID!\x1b[0m");
-                println!("\x1b[93m   Usage: trackr delete <id>\x1b[0m\n");
+                println!("\x1b[93m   Usage: trackr delete <id> [--children remove|reparent]\x1b[0m\n");
                 return;
             }
-            match args[2].parse::<u32>() {
-                Ok(id) => commands.delete(id),
-                Err(_) => {
-                    println!("\x1b[91m😿 Error: Invalid task ID! Must be a number.\x1b[0m\n");
+            let children = match args.get(3).map(|s| s.as_str()) {
+                None => None,
+                Some("--children") => match args.get(4).and_then(|value| ChildAction::from_str(value)) {
+                    Some(action) => Some(action),
+                    None => {
+                        println!("\x1b[91m😿 Error: --children must be remove or reparent!\x1b[0m\n");
+                        return;
+                    }
+                },
+                Some(other) => {
+                    println!("\x1b[91m😿 Error: Unknown option {}!\x1b[0m\n", other);
+                    return;
+                }
+            };
+            match commands.resolve_id(&args[2]) {
+                Ok(id) => commands.delete_with_children(id, children),
+                Err(e) => {
+                    println!("\x1b[91m😿 Error: {}\x1b[0m\n", e);
                 }
             }
         }
@@ -101,29 +232,318 @@ fn main() {
             if args.len() < 4 {
                 println!("\x1b[91m😿 Error: Please provide task ID and status!\x1b[0m");
                 println!("\x1b[93m   Usage: trackr mark <id> <status>\x1b[0m");
-                println!("\x1b[93m   Status options: todo, in-progress, done\x1b[0m\n");
+                println!("\x1b[93m   Status options: {}\x1b[0m\n", commands.status_names().join(", "));
                 return;
             }
-            match args[2].parse::<u32>() {
+            match commands.resolve_id(&args[2]) {
                 Ok(id) => {
                     let status = args[3].clone();
                     commands.mark(id, status);
                 }
-                Err(_) => {
-                    println!("\x1b[91m😿 Error: Invalid task ID! Must be a number.\x1b[0m\n");
+                Err(e) => {
+                    println!("\x1b[91m😿 Error: {}\x1b[0m\n", e);
+                }
+            }
+        }
+        "prio" => {
+            if args.len() < 4 {
+                println!("\x1b[91m😿 Error: Please provide task ID and priority!\x1b[0m");
+                println!("\x1b[93m   Usage: trackr prio <id> <level>\x1b[0m");
+                println!("\x1b[93m   Priority options: low, medium, high, critical, none\x1b[0m\n");
+                return;
+            }
+            match commands.resolve_id(&args[2]) {
+                Ok(id) => commands.prio(id, args[3].clone()),
+                Err(e) => {
+                    println!("\x1b[91m😿 Error: {}\x1b[0m\n", e);
+                }
+            }
+        }
+        "depend" | "undepend" => {
+            if args.len() < 4 {
+                println!("\x1b[91m😿 Error: Please provide both task IDs!\x1b[0m");
+                println!("\x1b[93m   Usage: trackr {} <id> <on-id>\x1b[0m\n", command);
+                return;
+            }
+            match (commands.resolve_id(&args[2]), commands.resolve_id(&args[3])) {
+                (Ok(id), Ok(on_id)) if command == "depend" => commands.depend(id, on_id),
+                (Ok(id), Ok(on_id)) => commands.undepend(id, on_id),
+                (Err(e), _) | (_, Err(e)) => {
+                    println!("\x1b[91m😿 Error: {}\x1b[0m\n", e);
+                }
+            }
+        }
+        "assign" => {
+            if args.len() < 3 {
+                println!("\x1b[91m😿 Error: Please provide a task ID!\x1b[0m");
+                println!("\x1b[93m   Usage: trackr assign <id> [name|none]\x1b[0m\n");
+                return;
+            }
+            match commands.resolve_id(&args[2]) {
+                Ok(id) => commands.assign(id, args.get(3).cloned()),
+                Err(e) => {
+                    println!("\x1b[91m😿 Error: {}\x1b[0m\n", e);
+                }
+            }
+        }
+        "tag" => {
+            if args.len() < 4 {
+                println!("\x1b[91m😿 Error: Please provide task ID and tags!\x1b[0m");
+                println!("\x1b[93m   Usage: trackr tag <id> +tag -tag...\x1b[0m\n");
+                return;
+            }
+            match commands.resolve_id(&args[2]) {
+                Ok(id) => commands.tag(id, args[3..].to_vec()),
+                Err(e) => {
+                    println!("\x1b[91m😿 Error: {}\x1b[0m\n", e);
                 }
             }
         }
+        "tags" => {
+            commands.tags();
+        }
+        "start" => {
+            if args.len() < 3 {
+                println!("\x1b[91m😿 Error: Please provide a task ID!\x1b[0m");
+                println!("\x1b[93m   Usage: trackr start <id>\x1b[0m\n");
+                return;
+            }
+            match commands.resolve_id(&args[2]) {
+                Ok(id) => commands.start(id),
+                Err(e) => {
+                    println!("\x1b[91m😿 Error: {}\x1b[0m\n", e);
+                }
+            }
+        }
+        "stop" => {
+            if args.len() < 3 {
+                commands.stop(None);
+                return;
+            }
+            match commands.resolve_id(&args[2]) {
+                Ok(id) => commands.stop(Some(id)),
+                Err(e) => {
+                    println!("\x1b[91m😿 Error: {}\x1b[0m\n", e);
+                }
+            }
+        }
+        "trash" => {
+            match args.get(2).map(|s| s.as_str()) {
+                None => commands.trash(),
+                Some("--empty") => match args.get(3).map(|s| s.as_str()) {
+                    None => commands.empty_trash(None),
+                    Some("--older-than") => match args.get(4).and_then(|value| datetime::parse_duration(value)) {
+                        Some(older_than) => commands.empty_trash(Some(older_than)),
+                        None => {
+                            println!("\x1b[91m😿 Error: --older-than needs a span like 30d, 2w or 12h!\x1b[0m\n");
+                        }
+                    },
+                    Some(other) => {
+                        println!("\x1b[91m😿 Error: Unknown option {}!\x1b[0m\n", other);
+                    }
+                },
+                Some(other) => {
+                    println!("\x1b[91m😿 Error: Unknown option {}!\x1b[0m", other);
+                    println!("\x1b[93m   Usage: trackr trash [--empty [--older-than 30d]]\x1b[0m\n");
+                }
+            }
+        }
+        "restore" => {
+            if args.len() < 3 {
+                println!("\x1b[91m😿 Error: Please provide a task ID!\x1b[0m");
+                println!("\x1b[93m   Usage: trackr restore <id>\x1b[0m\n");
+                return;
+            }
+            match commands.resolve_id(&args[2]) {
+                Ok(id) => commands.restore(id),
+                Err(e) => {
+                    println!("\x1b[91m😿 Error: {}\x1b[0m\n", e);
+                }
+            }
+        }
+        "archive" => {
+            if args.len() < 3 {
+                println!("\x1b[91m😿 Error: Please provide a task ID or --done!\x1b[0m");
+                println!("\x1b[93m   Usage: trackr archive <id>|--done\x1b[0m\n");
+                return;
+            }
+            if args[2] == "--done" {
+                commands.archive_done();
+                return;
+            }
+            match commands.resolve_id(&args[2]) {
+                Ok(id) => commands.archive(id),
+                Err(e) => {
+                    println!("\x1b[91m😿 Error: {}\x1b[0m\n", e);
+                }
+            }
+        }
+        "unarchive" => {
+            if args.len() < 3 {
+                println!("\x1b[91m😿 Error: Please provide a task ID!\x1b[0m");
+                println!("\x1b[93m   Usage: trackr unarchive <id>\x1b[0m\n");
+                return;
+            }
+            match commands.resolve_id(&args[2]) {
+                Ok(id) => commands.unarchive(id),
+                Err(e) => {
+                    println!("\x1b[91m😿 Error: {}\x1b[0m\n", e);
+                }
+            }
+        }
+        "show" => {
+            if args.len() < 3 {
+                println!("\x1b[91m😿 Error: Please provide a task ID!\x1b[0m");
+                println!("\x1b[93m   Usage: trackr show <id>\x1b[0m\n");
+                return;
+            }
+            match commands.resolve_id(&args[2]) {
+                Ok(id) => commands.show(id),
+                Err(e) => {
+                    println!("\x1b[91m😿 Error: {}\x1b[0m\n", e);
+                }
+            }
+        }
+        "annotate" => {
+            if args.len() < 4 {
+                println!("\x1b[91m😿 Error: Please provide task ID and note text!\x1b[0m");
+                println!("\x1b[93m   Usage: trackr annotate <id> <text>\x1b[0m\n");
+                return;
+            }
+            match commands.resolve_id(&args[2]) {
+                Ok(id) => commands.annotate(id, args[3..].join(" ")),
+                Err(e) => {
+                    println!("\x1b[91m😿 Error: {}\x1b[0m\n", e);
+                }
+            }
+        }
+        "denotate" => {
+            if args.len() < 4 {
+                println!("\x1b[91m😿 Error: Please provide task ID and note number!\x1b[0m");
+                println!("\x1b[93m   Usage: trackr denotate <id> <n>\x1b[0m\n");
+                return;
+            }
+            match (commands.resolve_id(&args[2]), args[3].parse::<usize>()) {
+                (Ok(id), Ok(position)) => commands.denotate(id, position),
+                (Err(e), _) => {
+                    println!("\x1b[91m😿 Error: {}\x1b[0m\n", e);
+                }
+                (_, Err(_)) => {
+                    println!("\x1b[91m😿 Error: Note number must be a number.\x1b[0m\n");
+                }
+            }
+        }
+        "context" => match args.get(2).map(|arg| arg.as_str()) {
+            None => commands.list_contexts(),
+            Some("none") => commands.set_context(None),
+            Some("set") => match args.get(3) {
+                Some(name) => commands.set_context(Some(name)),
+                None => {
+                    println!("\x1b[91m😿 Error: Please provide a context name!\x1b[0m");
+                    println!("\x1b[93m   Usage: trackr context set <name>\x1b[0m\n");
+                }
+            },
+            Some(other) => {
+                println!("\x1b[91m😿 Error: Unknown context command '{}'\x1b[0m", other);
+                println!("\x1b[93m   Usage: trackr context [set <name>|none]\x1b[0m\n");
+            }
+        },
+        "recover" => {
+            commands.recover();
+        }
+        "next" => {
+            commands.next();
+        }
+        "projects" => {
+            commands.projects();
+        }
         "list" => {
-            let filter = if args.len() >= 3 {
-                Some(args[2].clone())
-            } else {
-                None
-            };
-            commands.list(filter);
+            let mut options = ListOptions::default();
+            let mut iter = args[2..].iter();
+            while let Some(arg) = iter.next() {
+                match arg.as_str() {
+                    "--age" => options.show_age = true,
+                    "--overdue" => options.overdue = true,
+                    "--due-before" => {
+                        let Some(value) = flag_value(&mut iter, arg) else {
+                            return;
+                        };
+                        options.due_before = Some(value);
+                    }
+                    "--priority" => {
+                        let Some(value) = flag_value(&mut iter, arg) else {
+                            return;
+                        };
+                        options.priority = Some(value);
+                    }
+                    "--tag" => {
+                        let Some(value) = flag_value(&mut iter, arg) else {
+                            return;
+                        };
+                        options.tags.push(value.trim_start_matches('+').to_string());
+                    }
+                    "--project" => {
+                        let Some(value) = flag_value(&mut iter, arg) else {
+                            return;
+                        };
+                        options.project = Some(value);
+                    }
+                    "--group" => options.group_by_project = true,
+                    "--tree" => options.tree = true,
+                    "--ready" => options.ready = true,
+                    "--archived" => options.archived = true,
+                    "--all" => options.all = true,
+                    "--mine" => options.mine = true,
+                    "--where" => {
+                        let Some(value) = flag_value(&mut iter, arg) else {
+                            return;
+                        };
+                        options.wheres.push(value);
+                    }
+                    "--assignee" => {
+                        let Some(value) = flag_value(&mut iter, arg) else {
+                            return;
+                        };
+                        options.assignee = Some(value);
+                    }
+                    "--sort" => {
+                        let Some(value) = flag_value(&mut iter, arg) else {
+                            return;
+                        };
+                        options.sort = Some(value);
+                    }
+                    _ => options.status = Some(arg.clone()),
+                }
+            }
+            commands.list_with_options(options);
         }
         "reset" => {
-            commands.reset();
+            let mut options = ResetOptions::default();
+            let mut iter = args[2..].iter();
+            while let Some(arg) = iter.next() {
+                match arg.as_str() {
+                    "--yes" | "-y" => options.yes = true,
+                    "--dry-run" => options.dry_run = true,
+                    "--status" => {
+                        let Some(value) = flag_value(&mut iter, arg) else {
+                            return;
+                        };
+                        options.status = Some(value);
+                    }
+                    "--tag" => {
+                        let Some(value) = flag_value(&mut iter, arg) else {
+                            return;
+                        };
+                        options.tags.push(value.trim_start_matches('+').to_string());
+                    }
+                    other => {
+                        println!("\x1b[91m😿 Error: Unknown option {}!\x1b[0m", other);
+                        println!("\x1b[93m   Usage: trackr reset [--status <status>] [--tag <tag>] [--dry-run] [--yes]\x1b[0m\n");
+                        return;
+                    }
+                }
+            }
+            commands.reset_with(options);
         }
         "help" | "--help" | "-h" => {
             print_help();
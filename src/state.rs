@@ -0,0 +1,54 @@
+use std::fs;
+use std::path::Path;
+use crate::storage::{self, StorageError};
+
+/// Settings each person changes as they use trackr, such as the active
+/// context. They live in `~/.trackr_state` rather than the task file, so
+/// people sharing one task file don't change each other's view.
+pub struct State {
+    pub file_path: String,
+}
+
+impl State {
+    pub fn new(file_path: &str) -> State {
+        State { file_path: file_path.to_string() }
+    }
+
+    /// The name of the context set with `trackr context set`, if any.
+    pub fn context(&self) -> Option<String> {
+        self.get("context")
+    }
+
+    pub fn set_context(&self, name: Option<&str>) -> Result<(), StorageError> {
+        self.set("context", name)
+    }
+
+    // The file holds `key = value` lines. A missing or unreadable one is
+    // treated as empty, since nothing in it is worth stopping for.
+    fn read(&self) -> Vec<(String, String)> {
+        fs::read_to_string(&self.file_path).unwrap_or_default()
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+            .filter(|(_, value)| !value.is_empty())
+            .collect()
+    }
+
+    fn get(&self, key: &str) -> Option<String> {
+        self.read().into_iter().find(|(k, _)| k == key).map(|(_, value)| value)
+    }
+
+    fn set(&self, key: &str, value: Option<&str>) -> Result<(), StorageError> {
+        let mut entries = self.read();
+        entries.retain(|(k, _)| k != key);
+        if let Some(value) = value {
+            entries.push((key.to_string(), value.to_string()));
+        }
+
+        let contents: String = entries.iter().map(|(key, value)| format!("{} = {}\n", key, value)).collect();
+        storage::write_atomically(Path::new(&self.file_path), contents.as_bytes()).map_err(|e| StorageError::Write {
+            path: self.file_path.clone(),
+            reason: e.to_string(),
+        })
+    }
+}
@@ -1,67 +1,394 @@
-use std::fs::{File, OpenOptions};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
 use std::io::{Read, Write};
-use std::path::Path;
-use crate::task::{Task, TaskStatus};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::rc::Rc;
+use crate::config::{UdaDef, Workflow};
+use crate::datetime::{self, Date};
+use crate::json::{self, Json, JsonError, Value};
+use crate::task::{derived_uuid, Annotation, Priority, Recurrence, StatusChange, Task, TaskStatus, UdaValue, WorkInterval};
+
+/// Task lists kept next to the main one in the same file.
+const SIDE_SECTIONS: [&str; 2] = ["archive", "trash"];
+
+/// Why the task file couldn't be loaded or saved.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StorageError {
+    /// There is no file yet, which for a new user is normal.
+    Missing { path: String },
+    /// The file exists but couldn't be opened or read, e.g. for permissions
+    /// or because it isn't UTF-8.
+    Unreadable { path: String, reason: String },
+    /// The file was read but isn't a valid task file.
+    Corrupt { path: String, error: JsonError },
+    /// The new contents couldn't be written. The file on disk is left as it
+    /// was before the save.
+    Write { path: String, reason: String },
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StorageError::Missing { path } => write!(f, "{} doesn't exist", path),
+            StorageError::Unreadable { path, reason } => write!(f, "couldn't read {}: {}", path, reason),
+            StorageError::Corrupt { path, error } => write!(f, "{}: {}", path, error),
+            StorageError::Write { path, reason } => write!(f, "couldn't write {}: {}", path, reason),
+        }
+    }
+}
+
+/// What `Storage::recover` did with a broken file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recovery {
+    /// Where the broken file was copied to.
+    pub backup_path: String,
+    /// False if the file wasn't valid JSON at all, so nothing was kept.
+    pub parsed: bool,
+    pub kept: usize,
+    pub dropped: usize,
+}
 
 pub struct Storage {
     pub file_path: String,
+    workflow: Workflow,
+    udas: Vec<UdaDef>,
+    snapshot: RefCell<Option<Rc<Snapshot>>>,
+}
+
+// The file as it was last read, parsed once, with each task list converted
+// the first time it's asked for. A command reads the file several times
+// over, and while it holds the same bytes none of that work is repeated.
+struct Snapshot {
+    contents: String,
+    document: Json,
+    sections: RefCell<Vec<(String, Section)>>,
 }
 
+type Section = Rc<Result<Vec<Task>, JsonError>>;
+
 impl Storage {
     pub fn new(file_path: &str) -> Storage {
+        Storage::with_workflow(file_path, Workflow::default())
+    }
+
+    /// Storage that also recognises the statuses configured in `workflow`.
+    pub fn with_workflow(file_path: &str, workflow: Workflow) -> Storage {
         Storage {
             file_path: file_path.to_string(),
+            workflow,
+            udas: Vec::new(),
+            snapshot: RefCell::new(None),
         }
     }
 
+    /// Declares the user-defined attributes, so their values load typed.
+    pub fn with_udas(mut self, udas: Vec<UdaDef>) -> Storage {
+        self.udas = udas;
+        self
+    }
+
+    pub fn workflow(&self) -> &Workflow {
+        &self.workflow
+    }
+
+    pub fn uda(&self, name: &str) -> Option<&UdaDef> {
+        let name = name.to_lowercase();
+        self.udas.iter().find(|def| def.name == name)
+    }
+
+    pub fn udas(&self) -> &[UdaDef] {
+        &self.udas
+    }
+
+    /// The task list, or an empty one if the file is missing or broken.
+    /// Saving never overwrites a broken file, so nothing is lost by
+    /// carrying on; `read_tasks` says what went wrong.
     pub fn load_tasks(&self) -> Vec<Task> {
+        self.read_tasks().unwrap_or_default()
+    }
+
+    /// Tasks moved out of the main list by `trackr archive`.
+    pub fn load_archive(&self) -> Vec<Task> {
+        self.read_archive().unwrap_or_default()
+    }
+
+    /// Deleted tasks, each with its `deleted_at` time.
+    pub fn load_trash(&self) -> Vec<Task> {
+        self.read_trash().unwrap_or_default()
+    }
+
+    pub fn read_tasks(&self) -> Result<Vec<Task>, StorageError> {
+        self.read_section("tasks")
+    }
+
+    pub fn read_archive(&self) -> Result<Vec<Task>, StorageError> {
+        self.read_section("archive")
+    }
+
+    pub fn read_trash(&self) -> Result<Vec<Task>, StorageError> {
+        self.read_section("trash")
+    }
+
+    fn read_section(&self, key: &str) -> Result<Vec<Task>, StorageError> {
         if !Path::new(&self.file_path).exists() {
-            return Vec::new();
+            return Err(StorageError::Missing { path: self.file_path.clone() });
         }
 
-        let mut file = match File::open(&self.file_path) {
-            Ok(f) => f,
-            Err(_) => return Vec::new(),
+        match self.read_document()? {
+            Some(snapshot) => self.section(&snapshot, key).as_ref().clone().map_err(|error| self.corrupt(error)),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Parses the whole file, tasks included, and reports the first problem
+    /// with its line and column. A missing file is fine.
+    pub fn check(&self) -> Result<(), StorageError> {
+        let Some(snapshot) = self.read_document()? else {
+            return Ok(());
         };
 
-        let mut contents = String::new();
-        if file.read_to_string(&mut contents).is_err() {
-            return Vec::new();
+        for key in std::iter::once("tasks").chain(SIDE_SECTIONS) {
+            if let Err(error) = self.section(&snapshot, key).as_ref() {
+                return Err(self.corrupt(error.clone()));
+            }
         }
+        Ok(())
+    }
 
-        if contents.trim().is_empty() {
-            return Vec::new();
+    /// Copies a broken task file aside and replaces it with every task that
+    /// can still be read. Returns `None` if the file was fine.
+    pub fn recover(&self) -> Result<Option<Recovery>, StorageError> {
+        if self.check().is_ok() {
+            return Ok(None);
         }
 
-        self.parse_json(&contents)
+        let bytes = fs::read(&self.file_path).map_err(|e| StorageError::Unreadable {
+            path: self.file_path.clone(),
+            reason: e.to_string(),
+        })?;
+        // The backup is on disk before the file it copies is replaced.
+        let backup_path = format!("{}.broken-{}", self.file_path, datetime::now());
+        write_atomically(Path::new(&backup_path), &bytes).map_err(|e| StorageError::Write {
+            path: backup_path.clone(),
+            reason: e.to_string(),
+        })?;
+
+        let document = std::str::from_utf8(&bytes).ok().and_then(|contents| json::parse(contents).ok());
+        let mut recovery = Recovery { backup_path, parsed: document.is_some(), kept: 0, dropped: 0 };
+        let mut sections = Vec::new();
+        let mut next_id = 1;
+
+        for key in std::iter::once("tasks").chain(SIDE_SECTIONS) {
+            let items = match &document {
+                Some(document) if document.as_array().is_some() && key == "tasks" => document.as_array(),
+                Some(document) => document.get(key).and_then(|section| section.as_array()),
+                None => None,
+            };
+            let tasks: Vec<Task> = items.unwrap_or_default().iter()
+                .filter_map(|item| self.parse_task(item).ok())
+                .collect();
+
+            recovery.kept += tasks.len();
+            recovery.dropped += items.map_or(0, |items| items.len()) - tasks.len();
+            next_id = next_id.max(id_after(&tasks));
+            sections.push((key, self.task_array_json(&tasks)));
+        }
+
+        if let Some(document) = &document {
+            next_id = next_id.max(document.get("next_id").and_then(|n| n.as_u64()).unwrap_or(0));
+        }
+
+        self.write_file(&self.to_json(next_id, &sections))?;
+        Ok(Some(recovery))
     }
 
-    pub fn save_tasks(&self, tasks: &[Task]) -> Result<(), String> {
-        let json = self.to_json(tasks);
+    /// The id the next new task gets, or `None` once every id has been
+    /// used. Ids are never handed out twice, even after the task holding the
+    /// highest one is deleted.
+    pub fn next_id(&self, tasks: &[Task]) -> Option<u32> {
+        u32::try_from(self.id_counter(tasks)).ok()
+    }
 
-        let mut file = OpenOptions::new()
-            .write(true)
-            .create(true)
-            .truncate(true)
-            .open(&self.file_path)
-            .map_err(|e| format!("Failed to open file: {}", e))?;
+    // The stored counter can run one past the last possible id, which is how
+    // a file records that they're all used up.
+    fn id_counter(&self, tasks: &[Task]) -> u64 {
+        let counter = self.read_document().ok().flatten()
+            .and_then(|snapshot| snapshot.document.get("next_id")?.as_u64())
+            .unwrap_or(0);
+        counter.max(id_after(tasks))
+    }
 
-        file.write_all(json.as_bytes())
-            .map_err(|e| format!("Failed to write to file: {}", e))?;
+    pub fn save_tasks(&self, tasks: &[Task]) -> Result<(), StorageError> {
+        self.save(tasks, &[])
+    }
 
-        Ok(())
+    /// Saves the task list and the archive in one write, so a task moving
+    /// between them is never lost or duplicated.
+    pub fn save_with_archive(&self, tasks: &[Task], archive: &[Task]) -> Result<(), StorageError> {
+        self.save(tasks, &[("archive", archive)])
+    }
+
+    /// Saves the task list and the trash in one write.
+    pub fn save_with_trash(&self, tasks: &[Task], trash: &[Task]) -> Result<(), StorageError> {
+        self.save(tasks, &[("trash", trash)])
+    }
+
+    /// Removes every task, archived and trashed ones included. The id
+    /// counter stays, so ids are never handed out twice.
+    pub fn clear(&self) -> Result<(), StorageError> {
+        self.writable()?;
+        let everything = [self.load_tasks(), self.load_archive(), self.load_trash()].concat();
+        let next_id = self.id_counter(&everything);
+
+        let mut sections = vec![("tasks", "[]".to_string())];
+        sections.extend(SIDE_SECTIONS.iter().map(|key| (*key, "[]".to_string())));
+        self.write_file(&self.to_json(next_id, &sections))
+    }
+
+    fn save(&self, tasks: &[Task], changed: &[(&str, &[Task])]) -> Result<(), StorageError> {
+        let existing = self.writable()?;
+        self.write_sections(tasks, changed, existing.as_deref())
+    }
+
+    // The current contents, checked before anything is written. A file that
+    // couldn't be loaded is refused with the error that says why, since
+    // writing would replace tasks that were never read.
+    fn writable(&self) -> Result<Option<Rc<Snapshot>>, StorageError> {
+        self.check()?;
+        self.read_document()
+    }
+
+    fn corrupt(&self, error: JsonError) -> StorageError {
+        StorageError::Corrupt { path: self.file_path.clone(), error }
+    }
+
+    fn write_sections(&self, tasks: &[Task], changed: &[(&str, &[Task])], existing: Option<&Snapshot>) -> Result<(), StorageError> {
+        let mut next_id = self.id_counter(tasks);
+        let mut sections = vec![("tasks", self.task_array_json(tasks))];
+
+        for key in SIDE_SECTIONS {
+            let json = match changed.iter().find(|(changed_key, _)| *changed_key == key) {
+                Some((_, section)) => {
+                    next_id = next_id.max(id_after(section));
+                    self.task_array_json(section)
+                }
+                // Side sections can be large, so when they aren't changing
+                // their text is copied over as is rather than written out again.
+                None => existing
+                    .and_then(|existing| Some(existing.contents[existing.document.get(key)?.span()].to_string()))
+                    .unwrap_or_else(|| "[]".to_string()),
+            };
+            sections.push((key, json));
+        }
+
+        self.write_file(&self.to_json(next_id, &sections))
+    }
+
+    fn write_file(&self, json: &str) -> Result<(), StorageError> {
+        write_atomically(Path::new(&self.file_path), json.as_bytes()).map_err(|e| StorageError::Write {
+            path: self.file_path.clone(),
+            reason: e.to_string(),
+        })
+    }
+
+    // `None` for a missing or blank file.
+    fn read_file(&self) -> Result<Option<String>, StorageError> {
+        if !Path::new(&self.file_path).exists() {
+            return Ok(None);
+        }
+
+        let unreadable = |e: std::io::Error| StorageError::Unreadable {
+            path: self.file_path.clone(),
+            reason: e.to_string(),
+        };
+        let mut file = File::open(&self.file_path).map_err(unreadable)?;
+        let mut contents = String::new();
+        file.read_to_string(&mut contents).map_err(unreadable)?;
+
+        Ok(Some(contents).filter(|contents| !contents.trim().is_empty()))
+    }
+
+    fn read_document(&self) -> Result<Option<Rc<Snapshot>>, StorageError> {
+        let Some(contents) = self.read_file()? else {
+            return Ok(None);
+        };
+        if let Some(snapshot) = self.snapshot.borrow().as_ref()
+            && snapshot.contents == contents
+        {
+            return Ok(Some(Rc::clone(snapshot)));
+        }
+
+        let document = json::parse(&contents).map_err(|error| self.corrupt(error))?;
+        let snapshot = Rc::new(Snapshot { contents, document, sections: RefCell::new(Vec::new()) });
+        self.snapshot.replace(Some(Rc::clone(&snapshot)));
+        Ok(Some(snapshot))
     }
 
-    fn to_json(&self, tasks: &[Task]) -> String {
+    // One task list from `snapshot`, converted on first use.
+    fn section(&self, snapshot: &Snapshot, key: &str) -> Section {
+        if let Some((_, section)) = snapshot.sections.borrow().iter().find(|(name, _)| name == key) {
+            return Rc::clone(section);
+        }
+        let section = Rc::new(self.parse_section(&snapshot.document, key));
+        snapshot.sections.borrow_mut().push((key.to_string(), Rc::clone(&section)));
+        section
+    }
+
+    fn to_json(&self, next_id: u64, sections: &[(&str, String)]) -> String {
+        let mut json = format!("{{\n  \"next_id\": {}", next_id);
+        for (key, value) in sections {
+            json.push_str(&format!(",\n  \"{}\": {}", key, value));
+        }
+        json.push_str("\n}\n");
+        json
+    }
+
+    fn task_array_json(&self, tasks: &[Task]) -> String {
+        if tasks.is_empty() {
+            return "[]".to_string();
+        }
+
         let mut json = String::from("[\n");
 
         for (i, task) in tasks.iter().enumerate() {
-            json.push_str("  {\n");
-            json.push_str(&format!("    \"id\": {},\n", task.id));
-            json.push_str(&format!("    \"description\": \"{}\",\n",
-                self.escape_json(&task.description)));
-            json.push_str(&format!("    \"status\": \"{}\"\n", task.status.to_string()));
-            json.push_str("  }");
+            let fields = [
+                format!("\"id\": {}", task.id),
+                format!("\"uuid\": \"{}\"", task.uuid),
+                format!("\"description\": \"{}\"", self.escape_json(&task.description)),
+                format!("\"status\": \"{}\"", task.status.to_string()),
+                format!("\"created_at\": {}", self.timestamp_json(task.created_at)),
+                format!("\"updated_at\": {}", self.timestamp_json(task.updated_at)),
+                format!("\"completed_at\": {}", self.timestamp_json(task.completed_at)),
+                format!("\"due\": {}", self.date_json(task.due)),
+                format!("\"wait\": {}", self.date_json(task.wait)),
+                format!("\"priority\": {}", self.string_json(task.priority.map(|p| p.to_string()))),
+                format!("\"tags\": {}", self.string_array_json(&task.tags)),
+                format!("\"project\": {}", self.string_json(task.project.clone())),
+                format!("\"assignee\": {}", self.string_json(task.assignee.clone())),
+                format!("\"annotations\": {}", self.annotations_json(&task.annotations)),
+                format!("\"parent\": {}", self.number_json(task.parent)),
+                format!("\"depends_on\": {}", self.number_array_json(&task.depends_on)),
+                format!("\"recurrence\": {}", self.string_json(task.recurrence.as_ref().map(|r| r.to_string()))),
+                format!("\"recur_from\": {}", self.number_json(task.recur_from)),
+                format!("\"time_log\": {}", self.time_log_json(&task.time_log)),
+                format!("\"history\": {}", self.history_json(&task.history)),
+                format!("\"udas\": {}", self.udas_json(&task.udas)),
+                format!("\"deleted_at\": {}", self.timestamp_json(task.deleted_at)),
+            ];
+
+            json.push_str("    {\n");
+            for (j, field) in fields.iter().enumerate() {
+                json.push_str("      ");
+                json.push_str(field);
+                if j < fields.len() - 1 {
+                    json.push(',');
+                }
+                json.push('\n');
+            }
+            json.push_str("    }");
 
             if i < tasks.len() - 1 {
                 json.push(',');
@@ -69,109 +396,351 @@ impl Storage {
             json.push('\n');
         }
 
-        json.push_str("]\n");
+        json.push_str("  ]");
         json
     }
 
-    fn parse_json(&self, json: &str) -> Vec<Task> {
-        let mut tasks = Vec::new();
-        let trimmed = json.trim();
+    fn annotations_json(&self, annotations: &[Annotation]) -> String {
+        let items: Vec<String> = annotations.iter()
+            .map(|annotation| format!("{{\"created_at\": {}, \"text\": \"{}\"}}",
+                self.timestamp_json(Some(annotation.created_at)),
+                self.escape_json(&annotation.text)))
+            .collect();
+        format!("[{}]", items.join(", "))
+    }
+
+    fn time_log_json(&self, time_log: &[WorkInterval]) -> String {
+        let items: Vec<String> = time_log.iter()
+            .map(|interval| format!("{{\"start\": {}, \"end\": {}}}",
+                self.timestamp_json(Some(interval.start)),
+                self.timestamp_json(interval.end)))
+            .collect();
+        format!("[{}]", items.join(", "))
+    }
+
+    fn history_json(&self, history: &[StatusChange]) -> String {
+        let items: Vec<String> = history.iter()
+            .map(|change| format!("{{\"at\": {}, \"from\": \"{}\", \"to\": \"{}\"}}",
+                self.timestamp_json(Some(change.at)),
+                self.escape_json(&change.from.to_string()),
+                self.escape_json(&change.to.to_string())))
+            .collect();
+        format!("[{}]", items.join(", "))
+    }
+
+    fn udas_json(&self, udas: &BTreeMap<String, UdaValue>) -> String {
+        let items: Vec<String> = udas.iter()
+            .map(|(name, value)| match value {
+                UdaValue::Number(number) => format!("\"{}\": {}", self.escape_json(name), number),
+                _ => format!("\"{}\": \"{}\"", self.escape_json(name), self.escape_json(&value.to_string())),
+            })
+            .collect();
+        format!("{{{}}}", items.join(", "))
+    }
 
-        if !trimmed.starts_with('[') || !trimmed.ends_with(']') {
-            return tasks;
+    fn number_json(&self, value: Option<u32>) -> String {
+        match value {
+            Some(value) => value.to_string(),
+            None => "null".to_string(),
         }
+    }
+
+    fn number_array_json(&self, values: &[u32]) -> String {
+        let items: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+        format!("[{}]", items.join(", "))
+    }
 
-        let content = &trimmed[1..trimmed.len() - 1];
-        let mut current_object = String::new();
-        let mut brace_count = 0;
+    fn string_array_json(&self, values: &[String]) -> String {
+        let items: Vec<String> = values.iter()
+            .map(|value| format!("\"{}\"", self.escape_json(value)))
+            .collect();
+        format!("[{}]", items.join(", "))
+    }
 
-        for ch in content.chars() {
-            if ch == '{' {
-                brace_count += 1;
-                current_object.push(ch);
-            } else if ch == '}' {
-                current_object.push(ch);
-                brace_count -= 1;
+    fn string_json(&self, value: Option<String>) -> String {
+        match value {
+            Some(value) => format!("\"{}\"", self.escape_json(&value)),
+            None => "null".to_string(),
+        }
+    }
 
-                if brace_count == 0 {
-                    if let Some(task) = self.parse_task_object(&current_object) {
-                        tasks.push(task);
-                    }
-                    current_object.clear();
-                }
-            } else if brace_count > 0 {
-                current_object.push(ch);
-            }
+    fn date_json(&self, date: Option<Date>) -> String {
+        match date {
+            Some(date) => format!("\"{}\"", date),
+            None => "null".to_string(),
         }
+    }
 
-        tasks
+    fn timestamp_json(&self, timestamp: Option<u64>) -> String {
+        match timestamp {
+            Some(secs) => format!("\"{}\"", datetime::format_timestamp(secs)),
+            None => "null".to_string(),
+        }
     }
 
-    fn parse_task_object(&self, obj: &str) -> Option<Task> {
-        let mut id: Option<u32> = None;
-        let mut description: Option<String> = None;
-        let mut status: Option<TaskStatus> = None;
+    // Files written before the id counter are a bare task array.
+    fn parse_section(&self, document: &Json, key: &str) -> Result<Vec<Task>, JsonError> {
+        let section = match &document.value {
+            Value::Array(_) if key == "tasks" => document,
+            Value::Array(_) => return Ok(Vec::new()),
+            Value::Object(_) => match document.get(key) {
+                Some(section) => section,
+                None => return Ok(Vec::new()),
+            },
+            _ => return Err(document.error(format!("expected an object, found {}", document.type_name()))),
+        };
 
-        for line in obj.lines() {
-            let line = line.trim();
+        let items = section.as_array()
+            .ok_or_else(|| section.error(format!("\"{}\" should be an array, found {}", key, section.type_name())))?;
+        items.iter().map(|item| self.parse_task(item)).collect()
+    }
 
-            if line.contains("\"id\"") {
-                if let Some(value) = self.extract_number(line) {
-                    id = Some(value);
-                }
-            } else if line.contains("\"description\"") {
-                if let Some(value) = self.extract_string(line) {
-                    description = Some(value);
-                }
-            } else if line.contains("\"status\"") {
-                if let Some(value) = self.extract_string(line) {
-                    status = TaskStatus::from_str(&value);
+    fn parse_task(&self, object: &Json) -> Result<Task, JsonError> {
+        let fields = Fields::new(object, "a task")?;
+
+        let id = fields.require("id", "a whole number", whole_number)?;
+        let description = fields.require("description", "a string", string)?;
+        let status_value = fields.value("status")
+            .ok_or_else(|| object.error("missing \"status\"".to_string()))?;
+        let status_name = expect(status_value, "\"status\" should be a string", string)?;
+        let status = self.status_named(&status_name);
+
+        // Tasks saved before UUIDs existed get one derived from what's stored,
+        // so it's the same on every load until a save writes it out.
+        let uuid = match fields.convert("uuid", "a string", string)? {
+            Some(uuid) => uuid,
+            None => {
+                let created_at = fields.value("created_at").and_then(|v| v.as_str()).unwrap_or("");
+                derived_uuid(&format!("{}\n{}\n{}", id, description, created_at))
+            }
+        };
+
+        let mut task = Task::with_uuid(id, uuid, description, status);
+        task.created_at = fields.convert("created_at", "a timestamp", timestamp)?;
+        task.updated_at = fields.convert("updated_at", "a timestamp", timestamp)?;
+        task.completed_at = fields.convert("completed_at", "a timestamp", timestamp)?;
+        task.due = fields.convert("due", "a date like YYYY-MM-DD", date)?;
+        task.wait = fields.convert("wait", "a date like YYYY-MM-DD", date)?;
+        task.priority = fields.convert("priority", "low, medium, high or critical",
+            |v| Priority::from_str(v.as_str()?))?;
+        task.tags = fields.array("tags")?.iter()
+            .map(|v| expect(v, "expected a string", string))
+            .collect::<Result<_, _>>()?;
+        task.project = fields.convert("project", "a string", string)?;
+        task.assignee = fields.convert("assignee", "a string", string)?;
+        task.parent = fields.convert("parent", "a whole number", whole_number)?;
+        task.depends_on = fields.array("depends_on")?.iter()
+            .map(|v| expect(v, "expected a whole number", whole_number))
+            .collect::<Result<_, _>>()?;
+        task.recurrence = fields.convert("recurrence", "a recurrence rule",
+            |v| Recurrence::from_str(v.as_str()?))?;
+        task.recur_from = fields.convert("recur_from", "a whole number", whole_number)?;
+        task.deleted_at = fields.convert("deleted_at", "a timestamp", timestamp)?;
+
+        task.annotations = fields.array("annotations")?.iter()
+            .map(|item| {
+                let note = Fields::new(item, "a note")?;
+                Ok(Annotation {
+                    created_at: note.require("created_at", "a timestamp", timestamp)?,
+                    text: note.require("text", "a string", string)?,
+                })
+            })
+            .collect::<Result<_, JsonError>>()?;
+        task.time_log = fields.array("time_log")?.iter()
+            .map(|item| {
+                let interval = Fields::new(item, "a time interval")?;
+                Ok(WorkInterval {
+                    start: interval.require("start", "a timestamp", timestamp)?,
+                    end: interval.convert("end", "a timestamp", timestamp)?,
+                })
+            })
+            .collect::<Result<_, JsonError>>()?;
+        task.history = fields.array("history")?.iter()
+            .map(|item| {
+                let change = Fields::new(item, "a status change")?;
+                Ok(StatusChange {
+                    at: change.require("at", "a timestamp", timestamp)?,
+                    from: self.status_named(&change.require("from", "a string", string)?),
+                    to: self.status_named(&change.require("to", "a string", string)?),
+                })
+            })
+            .collect::<Result<_, JsonError>>()?;
+        if let Some(udas) = fields.value("udas") {
+            let members = udas.as_object()
+                .ok_or_else(|| udas.error(format!("\"udas\" should be an object, found {}", udas.type_name())))?;
+            for (name, value) in members {
+                if !value.is_null() {
+                    task.udas.insert(name.clone(), self.uda_value(name, value)?);
                 }
             }
         }
 
-        if let (Some(id), Some(description), Some(status)) = (id, description, status) {
-            Some(Task::with_status(id, description, status))
-        } else {
-            None
+        Ok(task)
+    }
+
+    // A status may have been removed from the config since it was written.
+    // Tasks and history records keep it as an open custom status rather than
+    // being dropped, and such a task can still be moved to any other status.
+    fn status_named(&self, name: &str) -> TaskStatus {
+        self.workflow.status(name).unwrap_or_else(|| TaskStatus::Custom {
+            name: name.to_string(),
+            closed: false,
+        })
+    }
+
+    // Values of attributes that are no longer declared, or whose type has
+    // changed, are kept as they were written.
+    fn uda_value(&self, name: &str, value: &Json) -> Result<UdaValue, JsonError> {
+        let fallback = match &value.value {
+            Value::Number(number) => UdaValue::Number(*number),
+            Value::String(text) => UdaValue::String(text.clone()),
+            _ => return Err(value.error(format!("\"{}\" should be a string or a number, found {}", name, value.type_name()))),
+        };
+        let raw = fallback.to_string();
+        Ok(self.uda(name)
+            .and_then(|def| def.parse_value(&raw).ok())
+            .unwrap_or(fallback))
+    }
+
+    fn escape_json(&self, s: &str) -> String {
+        let mut escaped = String::with_capacity(s.len());
+        for c in s.chars() {
+            match c {
+                '\\' => escaped.push_str("\\\\"),
+                '"' => escaped.push_str("\\\""),
+                '\n' => escaped.push_str("\\n"),
+                '\r' => escaped.push_str("\\r"),
+                '\t' => escaped.push_str("\\t"),
+                c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+                c => escaped.push(c),
+            }
         }
+        escaped
     }
+}
 
-    fn extract_number(&self, line: &str) -> Option<u32> {
-        if let Some(colon_pos) = line.find(':') {
-            let value_part = &line[colon_pos + 1..];
-            let cleaned: String = value_part.chars()
-                .filter(|c| c.is_numeric())
-                .collect();
-            cleaned.parse().ok()
-        } else {
-            None
+// The members of one JSON object in the file, with errors that point at the
+// offending value. Null counts as missing.
+struct Fields<'a> {
+    object: &'a Json,
+}
+
+impl<'a> Fields<'a> {
+    fn new(object: &'a Json, what: &str) -> Result<Fields<'a>, JsonError> {
+        match object.as_object() {
+            Some(_) => Ok(Fields { object }),
+            None => Err(object.error(format!("expected {}, found {}", what, object.type_name()))),
         }
     }
 
-    fn extract_string(&self, line: &str) -> Option<String> {
-        let parts: Vec<&str> = line.split('"').collect();
-        if parts.len() >= 4 {
-            Some(self.unescape_json(parts[3]))
-        } else {
-            None
+    fn value(&self, key: &str) -> Option<&'a Json> {
+        self.object.get(key).filter(|value| !value.is_null())
+    }
+
+    fn convert<T>(&self, key: &str, what: &str, convert: impl Fn(&Json) -> Option<T>) -> Result<Option<T>, JsonError> {
+        self.value(key)
+            .map(|value| convert(value).ok_or_else(|| value.error(format!("\"{}\" should be {}", key, what))))
+            .transpose()
+    }
+
+    fn require<T>(&self, key: &str, what: &str, convert: impl Fn(&Json) -> Option<T>) -> Result<T, JsonError> {
+        self.convert(key, what, convert)?
+            .ok_or_else(|| self.object.error(format!("missing \"{}\"", key)))
+    }
+
+    fn array(&self, key: &str) -> Result<&'a [Json], JsonError> {
+        match self.value(key) {
+            Some(value) => value.as_array()
+                .ok_or_else(|| value.error(format!("\"{}\" should be an array, found {}", key, value.type_name()))),
+            None => Ok(&[]),
         }
     }
+}
 
-    fn escape_json(&self, s: &str) -> String {
-        s.replace('\\', "\\\\")
-            .replace('"', "\\\"")
-            .replace('\n', "\\n")
-            .replace('\r', "\\r")
-            .replace('\t', "\\t")
+// One past the highest id in `tasks`, and at least 1.
+fn id_after(tasks: &[Task]) -> u64 {
+    tasks.iter().map(|t| t.id as u64 + 1).max().unwrap_or(1)
+}
+
+fn expect<T>(value: &Json, message: &str, convert: impl Fn(&Json) -> Option<T>) -> Result<T, JsonError> {
+    convert(value).ok_or_else(|| value.error(message.to_string()))
+}
+
+fn string(value: &Json) -> Option<String> {
+    value.as_str().map(str::to_string)
+}
+
+fn whole_number(value: &Json) -> Option<u32> {
+    value.as_u32()
+}
+
+fn timestamp(value: &Json) -> Option<u64> {
+    datetime::parse_timestamp(value.as_str()?)
+}
+
+fn date(value: &Json) -> Option<Date> {
+    Date::parse(value.as_str()?)
+}
+
+// Replaces `path` with `contents` so that a crash, a full disk or Ctrl-C
+// leaves either the old file or the new one, never a mix. The contents go to
+// a temp file next to it, which is synced and then renamed over the original.
+pub(crate) fn write_atomically(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let path = resolve_symlink(path);
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let temp_path = dir.join(format!(".{}.{}.tmp", name, process::id()));
+
+    let written = write_temp(&temp_path, &path, contents).and_then(|_| fs::rename(&temp_path, &path));
+    if written.is_err() {
+        fs::remove_file(&temp_path).ok();
     }
+    written?;
+
+    sync_dir(&dir);
+    Ok(())
+}
+
+// Writes through a symlink go to the file it points at, rather than replacing
+// the link itself. The target may not exist yet.
+fn resolve_symlink(path: &Path) -> PathBuf {
+    if let Ok(target) = fs::canonicalize(path) {
+        return target;
+    }
+    match fs::read_link(path) {
+        Ok(target) => path.parent().map_or(target.clone(), |dir| dir.join(&target)),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+fn write_temp(temp_path: &Path, path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(temp_path)?;
+
+    // The new file takes over the old one's permissions.
+    if let Ok(metadata) = fs::metadata(path) {
+        file.set_permissions(metadata.permissions())?;
+    }
+
+    file.write_all(contents)?;
+    file.sync_all()
+}
 
-    fn unescape_json(&self, s: &str) -> String {
-        s.replace("\\\"", "\"")
-            .replace("\\\\", "\\")
-            .replace("\\n", "\n")
-            .replace("\\r", "\r")
-            .replace("\\t", "\t")
+// Makes the rename itself durable. Not every platform can open or sync a
+// directory, and the file is already in place either way, so a failure here
+// isn't reported.
+#[cfg(unix)]
+fn sync_dir(dir: &Path) {
+    if let Ok(dir) = File::open(dir) {
+        dir.sync_all().ok();
     }
 }
 
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) {}
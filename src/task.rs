@@ -1,13 +1,20 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::fmt;
+use rand::Rng;
+use crate::datetime::Date;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum TaskStatus {
     Todo,
     InProgress,
     Done,
+    /// A status defined in the workflow config, such as `blocked` or `cancelled`.
+    Custom { name: String, closed: bool },
 }
 
 impl TaskStatus {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<TaskStatus> {
         match s.to_lowercase().as_str() {
             "todo" => Some(TaskStatus::Todo),
@@ -17,11 +24,13 @@ impl TaskStatus {
         }
     }
 
+    #[allow(clippy::inherent_to_string_shadow_display)]
     pub fn to_string(&self) -> String {
         match self {
             TaskStatus::Todo => "todo".to_string(),
             TaskStatus::InProgress => "in-progress".to_string(),
             TaskStatus::Done => "done".to_string(),
+            TaskStatus::Custom { name, .. } => name.clone(),
         }
     }
 
@@ -30,6 +39,16 @@ impl TaskStatus {
             TaskStatus::Todo => "📝",
             TaskStatus::InProgress => "⚡",
             TaskStatus::Done => "✨",
+            TaskStatus::Custom { .. } => "🔹",
+        }
+    }
+
+    /// Whether tasks in this status count as finished.
+    pub fn is_closed(&self) -> bool {
+        match self {
+            TaskStatus::Done => true,
+            TaskStatus::Custom { closed, .. } => *closed,
+            _ => false,
         }
     }
 }
@@ -40,29 +59,654 @@ impl fmt::Display for TaskStatus {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl Priority {
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Priority> {
+        match s.to_lowercase().as_str() {
+            "low" | "l" => Some(Priority::Low),
+            "medium" | "m" => Some(Priority::Medium),
+            "high" | "h" => Some(Priority::High),
+            "critical" | "c" => Some(Priority::Critical),
+            _ => None,
+        }
+    }
+
+    #[allow(clippy::inherent_to_string_shadow_display)]
+    pub fn to_string(&self) -> String {
+        match self {
+            Priority::Low => "low".to_string(),
+            Priority::Medium => "medium".to_string(),
+            Priority::High => "high".to_string(),
+            Priority::Critical => "critical".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_string())
+    }
+}
+
+const WEEKDAYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Recurrence {
+    Daily,
+    /// Weekdays as 0 = Monday through 6 = Sunday. Empty means every seven days.
+    Weekly(Vec<u32>),
+    Monthly,
+    /// Monthly on a fixed day, or the last day of months too short for it.
+    /// A monthly series keeps the day it started on this way, so one due on
+    /// the 31st doesn't drift to the 28th after February.
+    MonthlyOn(u32),
+    EveryDays(u32),
+}
+
+impl Recurrence {
+    /// Parses `daily`, `weekly`, `weekly:mon,thu`, `monthly`, `monthly:N` or `every:N`.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Recurrence> {
+        let s = s.to_lowercase();
+        let (kind, arg) = match s.split_once(':') {
+            Some((kind, arg)) => (kind, Some(arg)),
+            None => (s.as_str(), None),
+        };
+
+        match (kind, arg) {
+            ("daily", None) => Some(Recurrence::Daily),
+            ("monthly", None) => Some(Recurrence::Monthly),
+            ("monthly", Some(day)) => match day.trim().parse() {
+                Ok(day) if (1..=31).contains(&day) => Some(Recurrence::MonthlyOn(day)),
+                _ => None,
+            },
+            ("weekly", None) => Some(Recurrence::Weekly(Vec::new())),
+            ("weekly", Some(days)) => {
+                let mut weekdays = Vec::new();
+                for day in days.split(',') {
+                    let index = WEEKDAYS.iter().position(|d| *d == day.trim())? as u32;
+                    if !weekdays.contains(&index) {
+                        weekdays.push(index);
+                    }
+                }
+                weekdays.sort();
+                Some(Recurrence::Weekly(weekdays))
+            }
+            ("every", Some(n)) => match n.trim_end_matches('d').parse() {
+                Ok(n) if n > 0 => Some(Recurrence::EveryDays(n)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    #[allow(clippy::inherent_to_string_shadow_display)]
+    pub fn to_string(&self) -> String {
+        match self {
+            Recurrence::Daily => "daily".to_string(),
+            Recurrence::Weekly(days) if days.is_empty() => "weekly".to_string(),
+            Recurrence::Weekly(days) => {
+                let names: Vec<&str> = days.iter().map(|d| WEEKDAYS[*d as usize]).collect();
+                format!("weekly:{}", names.join(","))
+            }
+            Recurrence::Monthly => "monthly".to_string(),
+            Recurrence::MonthlyOn(day) => format!("monthly:{}", day),
+            Recurrence::EveryDays(n) => format!("every:{}", n),
+        }
+    }
+
+    /// The first occurrence strictly after `date`.
+    pub fn next_after(&self, date: Date) -> Date {
+        match self {
+            Recurrence::Daily => date.add_days(1),
+            Recurrence::EveryDays(n) => date.add_days(*n as i64),
+            Recurrence::Monthly => date.add_months(1),
+            Recurrence::MonthlyOn(day) => {
+                let this_month = date.add_months_on(0, *day);
+                if this_month > date { this_month } else { date.add_months_on(1, *day) }
+            }
+            Recurrence::Weekly(days) if days.is_empty() => date.add_days(7),
+            Recurrence::Weekly(days) => (1..=7)
+                .map(|offset| date.add_days(offset))
+                .find(|candidate| days.contains(&candidate.weekday()))
+                .unwrap_or_else(|| date.add_days(7)),
+        }
+    }
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_string())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Annotation {
+    pub created_at: u64,
+    pub text: String,
+}
+
+/// A stretch of work on a task. `end` is `None` while the timer is running.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkInterval {
+    pub start: u64,
+    pub end: Option<u64>,
+}
+
+impl WorkInterval {
+    pub fn duration(&self, now: u64) -> u64 {
+        self.end.unwrap_or(now).saturating_sub(self.start)
+    }
+}
+
+/// The value of a user-defined attribute. Enum values are kept as strings.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UdaValue {
+    String(String),
+    Number(f64),
+    Date(Date),
+}
+
+impl UdaValue {
+    /// Orders values of the same kind; mixed kinds sort numbers, then dates,
+    /// then strings.
+    pub fn compare(&self, other: &UdaValue) -> Ordering {
+        match (self, other) {
+            (UdaValue::Number(a), UdaValue::Number(b)) => a.total_cmp(b),
+            (UdaValue::Date(a), UdaValue::Date(b)) => a.cmp(b),
+            (UdaValue::String(a), UdaValue::String(b)) => a.cmp(b),
+            _ => self.rank().cmp(&other.rank()),
+        }
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            UdaValue::Number(_) => 0,
+            UdaValue::Date(_) => 1,
+            UdaValue::String(_) => 2,
+        }
+    }
+}
+
+impl fmt::Display for UdaValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UdaValue::String(value) => write!(f, "{}", value),
+            UdaValue::Number(value) => write!(f, "{}", value),
+            UdaValue::Date(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// One status change, as recorded by `Task::set_status`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusChange {
+    pub at: u64,
+    pub from: TaskStatus,
+    pub to: TaskStatus,
+}
+
 #[derive(Debug, Clone)]
 pub struct Task {
     pub id: u32,
+    /// Random v4 UUID. Unlike `id` it stays unique across machines and merges.
+    pub uuid: String,
     pub description: String,
     pub status: TaskStatus,
+    pub created_at: Option<u64>,
+    pub updated_at: Option<u64>,
+    pub completed_at: Option<u64>,
+    pub due: Option<Date>,
+    /// Hidden from the default list until this day.
+    pub wait: Option<Date>,
+    pub priority: Option<Priority>,
+    pub tags: Vec<String>,
+    pub project: Option<String>,
+    pub assignee: Option<String>,
+    pub annotations: Vec<Annotation>,
+    pub parent: Option<u32>,
+    pub depends_on: Vec<u32>,
+    pub recurrence: Option<Recurrence>,
+    /// For recurring tasks, the id of the task the series started from.
+    pub recur_from: Option<u32>,
+    pub time_log: Vec<WorkInterval>,
+    /// Every status change, oldest first.
+    pub history: Vec<StatusChange>,
+    /// User-defined attributes, by name.
+    pub udas: BTreeMap<String, UdaValue>,
+    /// Set while the task sits in the trash.
+    pub deleted_at: Option<u64>,
 }
 
 impl Task {
     pub fn new(id: u32, description: String) -> Task {
         Task {
             id,
+            uuid: new_uuid(),
             description,
             status: TaskStatus::Todo,
+            created_at: None,
+            updated_at: None,
+            completed_at: None,
+            due: None,
+            wait: None,
+            priority: None,
+            tags: Vec::new(),
+            project: None,
+            assignee: None,
+            annotations: Vec::new(),
+            parent: None,
+            depends_on: Vec::new(),
+            recurrence: None,
+            recur_from: None,
+            time_log: Vec::new(),
+            history: Vec::new(),
+            udas: BTreeMap::new(),
+            deleted_at: None,
         }
     }
 
     pub fn with_status(id: u32, description: String, status: TaskStatus) -> Task {
+        Task::with_uuid(id, new_uuid(), description, status)
+    }
+
+    /// A task that already has a UUID, such as one read back from the file.
+    pub fn with_uuid(id: u32, uuid: String, description: String, status: TaskStatus) -> Task {
         Task {
             id,
+            uuid,
             description,
             status,
+            created_at: None,
+            updated_at: None,
+            completed_at: None,
+            due: None,
+            wait: None,
+            priority: None,
+            tags: Vec::new(),
+            project: None,
+            assignee: None,
+            annotations: Vec::new(),
+            parent: None,
+            depends_on: Vec::new(),
+            recurrence: None,
+            recur_from: None,
+            time_log: Vec::new(),
+            history: Vec::new(),
+            udas: BTreeMap::new(),
+            deleted_at: None,
+        }
+    }
+
+    /// Records a modification at `now`.
+    pub fn touch(&mut self, now: u64) {
+        self.updated_at = Some(now);
+    }
+
+    /// Changes the status, keeping `completed_at` in sync with it and
+    /// recording the change in `history`.
+    pub fn set_status(&mut self, status: TaskStatus, now: u64) {
+        if status != self.status {
+            self.history.push(StatusChange { at: now, from: self.status.clone(), to: status.clone() });
+        }
+
+        if status.is_closed() {
+            if !self.status.is_closed() || self.completed_at.is_none() {
+                self.completed_at = Some(now);
+            }
+        } else {
+            self.completed_at = None;
+        }
+
+        self.status = status;
+        self.touch(now);
+    }
+
+    pub fn is_valid_tag(tag: &str) -> bool {
+        !tag.is_empty() && !tag.chars().any(|c| c.is_whitespace() || c == ',' || c == '"')
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
+    /// Adds `tag` unless it is already present. Returns whether it was added.
+    pub fn add_tag(&mut self, tag: &str) -> bool {
+        if self.has_tag(tag) {
+            return false;
+        }
+        self.tags.push(tag.to_string());
+        true
+    }
+
+    /// Removes `tag` if present. Returns whether it was removed.
+    pub fn remove_tag(&mut self, tag: &str) -> bool {
+        let initial_len = self.tags.len();
+        self.tags.retain(|t| t != tag);
+        self.tags.len() < initial_len
+    }
+
+    /// Project paths are dot-separated segments such as `infra.ci.runners`.
+    pub fn is_valid_project(project: &str) -> bool {
+        project.split('.').all(|segment| {
+            !segment.is_empty() && !segment.chars().any(|c| c.is_whitespace() || c == '"')
+        })
+    }
+
+    pub fn is_valid_assignee(name: &str) -> bool {
+        !name.is_empty() && !name.chars().any(|c| c.is_whitespace() || c == ',' || c == '"')
+    }
+
+    /// Names are compared case-insensitively.
+    pub fn is_assigned_to(&self, name: &str) -> bool {
+        self.assignee.as_ref().is_some_and(|assignee| assignee.eq_ignore_ascii_case(name))
+    }
+
+    /// True if the task's project is `prefix` or one of its sub-projects.
+    pub fn in_project(&self, prefix: &str) -> bool {
+        match &self.project {
+            Some(project) => project == prefix
+                || project.strip_prefix(prefix).is_some_and(|rest| rest.starts_with('.')),
+            None => false,
+        }
+    }
+
+    pub fn annotate(&mut self, text: String, now: u64) {
+        self.annotations.push(Annotation { created_at: now, text });
+        self.touch(now);
+    }
+
+    /// Removes the annotation at 1-based `position`, as numbered by `trackr show`.
+    pub fn denotate(&mut self, position: usize, now: u64) -> Option<Annotation> {
+        if position == 0 || position > self.annotations.len() {
+            return None;
+        }
+        let removed = self.annotations.remove(position - 1);
+        self.touch(now);
+        Some(removed)
+    }
+
+    pub fn is_tracking(&self) -> bool {
+        self.time_log.last().is_some_and(|interval| interval.end.is_none())
+    }
+
+    /// Opens a new work interval. Returns false if a timer is already running.
+    pub fn start_timer(&mut self, now: u64) -> bool {
+        if self.is_tracking() {
+            return false;
         }
+        self.time_log.push(WorkInterval { start: now, end: None });
+        self.touch(now);
+        true
+    }
+
+    /// Closes the running interval and returns its length.
+    pub fn stop_timer(&mut self, now: u64) -> Option<u64> {
+        let interval = self.time_log.last_mut().filter(|interval| interval.end.is_none())?;
+        interval.end = Some(now.max(interval.start));
+        let duration = interval.duration(now);
+        self.touch(now);
+        Some(duration)
+    }
+
+    /// Total tracked seconds, counting a running timer up to `now`.
+    pub fn time_spent(&self, now: u64) -> u64 {
+        self.time_log.iter().map(|interval| interval.duration(now)).sum()
     }
+
+    /// Builds the next todo instance of a recurring task, due on the first
+    /// occurrence after this one that isn't already in the past.
+    pub fn next_occurrence(&self, id: u32, today: Date, now: u64) -> Option<Task> {
+        let rule = self.recurrence.as_ref()?;
+        let start = self.due.unwrap_or(today);
+        let rule = match rule {
+            Recurrence::Monthly => Recurrence::MonthlyOn(start.day),
+            rule => rule.clone(),
+        };
+
+        let mut due = rule.next_after(start);
+        while due < today {
+            due = rule.next_after(due);
+        }
+
+        let mut next = Task::new(id, self.description.clone());
+        next.created_at = Some(now);
+        next.touch(now);
+        next.due = Some(due);
+        next.priority = self.priority;
+        next.tags = self.tags.clone();
+        next.project = self.project.clone();
+        next.assignee = self.assignee.clone();
+        next.udas = self.udas.clone();
+        next.parent = self.parent;
+        next.recurrence = Some(rule);
+        next.recur_from = Some(self.recur_from.unwrap_or(self.id));
+        Some(next)
+    }
+
+    pub fn is_overdue(&self, today: Date) -> bool {
+        !self.status.is_closed() && self.due.is_some_and(|due| due < today)
+    }
+
+    pub fn is_waiting(&self, today: Date) -> bool {
+        self.wait.is_some_and(|wait| wait > today)
+    }
+
+    pub fn is_due_today(&self, today: Date) -> bool {
+        !self.status.is_closed() && self.due == Some(today)
+    }
+
+    /// Seconds since the task was created, if its creation time is known.
+    pub fn age(&self, now: u64) -> Option<u64> {
+        self.created_at.map(|created| now.saturating_sub(created))
+    }
+}
+
+/// A random (version 4) UUID in the usual hyphenated lowercase form.
+pub fn new_uuid() -> String {
+    let bytes: [u8; 16] = rand::thread_rng().r#gen();
+    format_uuid(bytes, 4)
 }
 
+/// A UUID that is the same every time for the same `seed`. Tasks saved
+/// before UUIDs existed get theirs this way, so it doesn't change from one
+/// run to the next before the file is saved with it.
+pub fn derived_uuid(seed: &str) -> String {
+    // Two FNV-1a passes with different starting points give the 128 bits.
+    let fnv = |basis: u64| seed.bytes().fold(basis, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100_0000_01b3));
+    let mut bytes = [0u8; 16];
+    bytes[..8].copy_from_slice(&fnv(0xcbf2_9ce4_8422_2325).to_be_bytes());
+    bytes[8..].copy_from_slice(&fnv(0x6c62_272e_07bb_0142).to_be_bytes());
+    // Version 8 marks a UUID built some custom way rather than at random.
+    format_uuid(bytes, 8)
+}
+
+fn format_uuid(mut bytes: [u8; 16], version: u8) -> String {
+    bytes[6] = (bytes[6] & 0x0f) | (version << 4);
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
 
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("{}-{}-{}-{}-{}", &hex[0..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..32])
+}
+
+/// Turns a task reference from the command line into a numeric id. The
+/// reference is either the id itself or a prefix of exactly one task's UUID.
+/// Numbers that aren't an existing id are tried as UUID prefixes only when
+/// they're long enough not to be a mistyped id.
+pub fn resolve_id(tasks: &[Task], reference: &str) -> Result<u32, String> {
+    const MIN_NUMERIC_PREFIX: usize = 6;
+
+    let numeric = reference.parse::<u32>().ok();
+    if let Some(id) = numeric
+        && (reference.len() < MIN_NUMERIC_PREFIX || tasks.iter().any(|t| t.id == id))
+    {
+        return Ok(id);
+    }
+
+    let prefix = reference.to_lowercase();
+    if prefix.is_empty() || !prefix.chars().all(|c| c.is_ascii_hexdigit() || c == '-') {
+        return Err("Invalid task ID! Use a number or a UUID prefix.".to_string());
+    }
+
+    let matches: Vec<u32> = tasks.iter()
+        .filter(|t| t.uuid.starts_with(&prefix))
+        .map(|t| t.id)
+        .collect();
+    match matches.as_slice() {
+        [id] => Ok(*id),
+        // Leave unknown numbers to the command so it reports a missing task.
+        [] => numeric.ok_or_else(|| format!("No task has a UUID starting with {}!", prefix)),
+        _ => {
+            let ids: Vec<String> = matches.iter().map(|id| format!("#{}", id)).collect();
+            Err(format!("{} matches several tasks ({}), add more characters!", prefix, ids.join(", ")))
+        }
+    }
+}
+
+/// Number of tasks carrying each tag, sorted by tag name.
+pub fn tag_counts(tasks: &[Task]) -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new();
+    for task in tasks {
+        for tag in &task.tags {
+            *counts.entry(tag.clone()).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+/// Ids of the tasks directly below `id`. A task that names itself as its
+/// parent isn't its own child.
+pub fn children_of(tasks: &[Task], id: u32) -> Vec<u32> {
+    tasks.iter()
+        .filter(|t| t.parent == Some(id) && t.id != id)
+        .map(|t| t.id)
+        .collect()
+}
+
+/// Ids of every task below `id` in the hierarchy, children first.
+pub fn descendants_of(tasks: &[Task], id: u32) -> Vec<u32> {
+    let mut descendants = children_of(tasks, id);
+    let mut i = 0;
+    while i < descendants.len() {
+        for child in children_of(tasks, descendants[i]) {
+            if child != id && !descendants.contains(&child) {
+                descendants.push(child);
+            }
+        }
+        i += 1;
+    }
+    descendants
+}
+
+/// The order `list --tree` draws `tasks` in, each with its depth. A task
+/// whose parent isn't in the list is a root. Tasks caught in a parent cycle
+/// have no root above them, so whatever is left over is drawn as extra roots.
+pub fn tree_order<'a>(tasks: &[&'a Task]) -> Vec<(&'a Task, usize)> {
+    let mut order = Vec::new();
+    let mut visited = vec![false; tasks.len()];
+
+    let is_root = |task: &Task| task.parent.is_none_or(|p| !tasks.iter().any(|t| t.id == p));
+    for i in 0..tasks.len() {
+        if is_root(tasks[i]) {
+            push_subtree(tasks, i, 0, &mut visited, &mut order);
+        }
+    }
+    for i in 0..tasks.len() {
+        push_subtree(tasks, i, 0, &mut visited, &mut order);
+    }
+    order
+}
+
+// Each task is drawn once, so a cycle stops where it comes back around.
+fn push_subtree<'a>(tasks: &[&'a Task], index: usize, depth: usize, visited: &mut [bool], order: &mut Vec<(&'a Task, usize)>) {
+    if visited[index] {
+        return;
+    }
+    visited[index] = true;
+    order.push((tasks[index], depth));
+
+    for child in 0..tasks.len() {
+        if tasks[child].parent == Some(tasks[index].id) {
+            push_subtree(tasks, child, depth + 1, visited, order);
+        }
+    }
+}
+
+/// Ids of the tasks `task` depends on that are not done yet. Dependencies on
+/// tasks that no longer exist count as satisfied.
+pub fn open_blockers(tasks: &[Task], task: &Task) -> Vec<u32> {
+    task.depends_on.iter()
+        .copied()
+        .filter(|dep| tasks.iter().any(|t| t.id == *dep && !t.status.is_closed()))
+        .collect()
+}
+
+/// A todo task whose dependencies are all done.
+pub fn is_ready(tasks: &[Task], task: &Task) -> bool {
+    task.status == TaskStatus::Todo && open_blockers(tasks, task).is_empty()
+}
+
+/// True if `from` depends on `target`, directly or through other tasks.
+pub fn depends_transitively(tasks: &[Task], from: u32, target: u32) -> bool {
+    let mut pending = vec![from];
+    let mut seen = Vec::new();
+
+    while let Some(id) = pending.pop() {
+        if seen.contains(&id) {
+            continue;
+        }
+        seen.push(id);
+
+        if let Some(task) = tasks.iter().find(|t| t.id == id) {
+            if task.depends_on.contains(&target) {
+                return true;
+            }
+            pending.extend(task.depends_on.iter().copied());
+        }
+    }
+
+    false
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ProjectSummary {
+    pub todo: usize,
+    pub in_progress: usize,
+    pub done: usize,
+}
+
+/// Status counts per project, with every task also counted towards each
+/// parent project (`infra.ci` rolls up into `infra`).
+pub fn project_summary(tasks: &[Task]) -> BTreeMap<String, ProjectSummary> {
+    let mut summary: BTreeMap<String, ProjectSummary> = BTreeMap::new();
+    for task in tasks {
+        let Some(project) = &task.project else {
+            continue;
+        };
+
+        let mut path = String::new();
+        for segment in project.split('.') {
+            if !path.is_empty() {
+                path.push('.');
+            }
+            path.push_str(segment);
+
+            let counts = summary.entry(path.clone()).or_default();
+            // Configured statuses count as done when closed and todo otherwise.
+            match &task.status {
+                TaskStatus::InProgress => counts.in_progress += 1,
+                status if status.is_closed() => counts.done += 1,
+                _ => counts.todo += 1,
+            }
+        }
+    }
+    summary
+}
@@ -0,0 +1,117 @@
+use trackr::commands::Commands;
+use trackr::storage::Storage;
+use trackr::task::{Annotation, Task};
+use std::fs::{self, File};
+use std::io::Write;
+
+#[test]
+fn test_annotate_and_denotate() {
+    let mut task = Task::new(1, "Investigate".to_string());
+
+    task.annotate("first".to_string(), 100);
+    task.annotate("second".to_string(), 200);
+    assert_eq!(task.annotations.len(), 2);
+    assert_eq!(task.updated_at, Some(200));
+
+    assert_eq!(task.denotate(0, 300), None);
+    assert_eq!(task.denotate(3, 300), None);
+
+    let removed = task.denotate(1, 300).unwrap();
+    assert_eq!(removed, Annotation { created_at: 100, text: "first".to_string() });
+    assert_eq!(task.annotations[0].text, "second");
+    assert_eq!(task.updated_at, Some(300));
+}
+
+#[test]
+fn test_multiline_annotations_round_trip() {
+    let test_file = "test_annotations_round_trip.json";
+    let storage = Storage::new(test_file);
+
+    let tricky = [
+        "Line one\nLine two\n\nLine four",
+        "Quotes \"inside\" and a backslash \\ here",
+        "Literal backslash-n: \\n is not a newline",
+        "Braces { and } and [brackets], commas",
+        "Control \u{1} char and tab\there",
+    ];
+
+    let mut task = Task::new(1, "Annotated".to_string());
+    for (i, text) in tricky.iter().enumerate() {
+        task.annotate(text.to_string(), 1_700_000_000 + i as u64);
+    }
+    storage.save_tasks(&[task, Task::new(2, "Next".to_string())]).unwrap();
+
+    let loaded = storage.load_tasks();
+    assert_eq!(loaded.len(), 2);
+    assert_eq!(loaded[0].annotations.len(), tricky.len());
+    for (i, text) in tricky.iter().enumerate() {
+        assert_eq!(loaded[0].annotations[i].text, *text);
+        assert_eq!(loaded[0].annotations[i].created_at, 1_700_000_000 + i as u64);
+    }
+    assert!(loaded[1].annotations.is_empty());
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_description_with_escapes_round_trips() {
+    let test_file = "test_annotations_description_escapes.json";
+    let storage = Storage::new(test_file);
+
+    let description = "Say \"hi\" to C:\\new\\folder {now}";
+    storage.save_tasks(&[Task::new(1, description.to_string())]).unwrap();
+
+    let loaded = storage.load_tasks();
+    assert_eq!(loaded[0].description, description);
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_unicode_escapes_are_decoded() {
+    let test_file = "test_annotations_unicode.json";
+
+    let json = r#"[
+  {
+    "id": 1,
+    "description": "caf\u00e9 \ud83d\ude38",
+    "status": "todo",
+    "annotations": [{"created_at": "2026-10-18T09:00:00Z", "text": "a\u000ab"}]
+  }
+]"#;
+
+    let mut file = File::create(test_file).unwrap();
+    file.write_all(json.as_bytes()).unwrap();
+    drop(file);
+
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks[0].description, "café 😸");
+    assert_eq!(tasks[0].annotations[0].text, "a\nb");
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_annotate_commands() {
+    let test_file = "test_annotations_commands.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.add("Debug flaky test".to_string());
+    commands.annotate(1, "see https://example.com/ci/123".to_string());
+    commands.annotate(1, "decided to retry\nafter lunch".to_string());
+    commands.annotate(99, "nowhere".to_string());
+    commands.show(1);
+
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks[0].annotations.len(), 2);
+    assert_eq!(tasks[0].annotations[1].text, "decided to retry\nafter lunch");
+
+    commands.denotate(1, 5);
+    commands.denotate(1, 1);
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks[0].annotations.len(), 1);
+    assert_eq!(tasks[0].annotations[0].text, "decided to retry\nafter lunch");
+
+    fs::remove_file(test_file).ok();
+}
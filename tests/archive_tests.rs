@@ -0,0 +1,118 @@
+use trackr::commands::{Commands, TaskOptions};
+use trackr::storage::Storage;
+use trackr::task::{Task, TaskStatus};
+use std::fs;
+
+#[test]
+fn test_archive_and_unarchive() {
+    let test_file = "test_archive_round_trip.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(Storage::new(test_file));
+    for name in ["Alpha", "Beta", "Gamma"] {
+        commands.add(name.to_string());
+    }
+    commands.mark(2, "done".to_string());
+    commands.archive(2);
+
+    let storage = Storage::new(test_file);
+    assert_eq!(storage.load_tasks().iter().map(|t| t.id).collect::<Vec<_>>(), vec![1, 3]);
+    let archive = storage.load_archive();
+    assert_eq!(archive.len(), 1);
+    assert_eq!(archive[0].description, "Beta");
+    assert_eq!(archive[0].status, TaskStatus::Done);
+
+    // Later saves keep the archive intact.
+    commands.add("Delta".to_string());
+    assert_eq!(storage.load_archive().len(), 1);
+    assert_eq!(storage.load_tasks().last().unwrap().id, 4);
+
+    commands.unarchive(2);
+    assert!(storage.load_archive().is_empty());
+    assert_eq!(storage.load_tasks().iter().map(|t| t.id).collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_archive_refuses_open_tasks() {
+    let test_file = "test_archive_open.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.add("Parent".to_string());
+    let child = TaskOptions { parent: Some("1".to_string()), ..TaskOptions::default() };
+    commands.add_with_options("Child".to_string(), child);
+
+    commands.archive(2);
+    commands.mark(1, "done".to_string());
+    commands.archive(1);
+    commands.archive(99);
+    commands.unarchive(1);
+
+    let storage = Storage::new(test_file);
+    assert_eq!(storage.load_tasks().len(), 2);
+    assert!(storage.load_archive().is_empty());
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_archive_done_moves_every_closed_task() {
+    let test_file = "test_archive_done.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(Storage::new(test_file));
+    for name in ["One", "Two", "Three", "Four"] {
+        commands.add(name.to_string());
+    }
+    commands.mark(1, "done".to_string());
+    commands.mark(3, "done".to_string());
+    commands.mark(4, "in-progress".to_string());
+    commands.archive_done();
+
+    let storage = Storage::new(test_file);
+    assert_eq!(storage.load_tasks().iter().map(|t| t.id).collect::<Vec<_>>(), vec![2, 4]);
+    assert_eq!(storage.load_archive().iter().map(|t| t.id).collect::<Vec<_>>(), vec![1, 3]);
+
+    commands.mark(2, "done".to_string());
+    commands.archive_done();
+    assert_eq!(storage.load_archive().iter().map(|t| t.id).collect::<Vec<_>>(), vec![1, 3, 2]);
+
+    // Archived ids stay taken.
+    commands.delete(4);
+    commands.add("Five".to_string());
+    assert_eq!(storage.load_tasks()[0].id, 5);
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_archived_tasks_resolve_by_uuid() {
+    let test_file = "test_archive_uuid.json";
+    fs::remove_file(test_file).ok();
+
+    let storage = Storage::new(test_file);
+    let mut task = Task::new(1, "Old".to_string());
+    task.uuid = "abcdef01-0000-4000-8000-000000000000".to_string();
+    storage.save_with_archive(&[], &[task]).unwrap();
+
+    let commands = Commands::new(Storage::new(test_file));
+    assert_eq!(commands.resolve_id("abcd"), Ok(1));
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_reset_clears_archive() {
+    let test_file = "test_archive_reset.json";
+    fs::remove_file(test_file).ok();
+
+    let storage = Storage::new(test_file);
+    storage.save_with_archive(&[], &[Task::new(1, "Old".to_string())]).unwrap();
+    Commands::new(Storage::new(test_file)).reset();
+
+    assert!(storage.load_archive().is_empty());
+
+    fs::remove_file(test_file).ok();
+}
@@ -0,0 +1,96 @@
+use trackr::commands::{Commands, ListOptions};
+use trackr::config::Config;
+use trackr::storage::Storage;
+use trackr::task::Task;
+use std::fs;
+
+#[test]
+fn test_valid_assignee_names() {
+    assert!(Task::is_valid_assignee("sam"));
+    assert!(Task::is_valid_assignee("sam.lee"));
+    assert!(!Task::is_valid_assignee(""));
+    assert!(!Task::is_valid_assignee("sam lee"));
+    assert!(!Task::is_valid_assignee("sam,lee"));
+    assert!(!Task::is_valid_assignee("\"sam\""));
+}
+
+#[test]
+fn test_is_assigned_to_ignores_case() {
+    let mut task = Task::new(1, "Review".to_string());
+    assert!(!task.is_assigned_to("sam"));
+
+    task.assignee = Some("Sam".to_string());
+    assert!(task.is_assigned_to("sam"));
+    assert!(!task.is_assigned_to("alex"));
+}
+
+#[test]
+fn test_assign_round_trip() {
+    let test_file = "test_assignee_round_trip.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(Storage::new(test_file)).with_user(Some("me".to_string()));
+    commands.add("Write docs".to_string());
+    commands.add("Fix build".to_string());
+    commands.add("Triage".to_string());
+
+    commands.assign(1, Some("sam".to_string()));
+    commands.assign(2, None);
+    commands.assign(3, Some("not valid".to_string()));
+
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks[0].assignee, Some("sam".to_string()));
+    assert_eq!(tasks[1].assignee, Some("me".to_string()));
+    assert_eq!(tasks[2].assignee, None);
+
+    let contents = fs::read_to_string(test_file).unwrap();
+    assert!(contents.contains("\"assignee\": \"sam\""));
+    assert!(contents.contains("\"assignee\": null"));
+
+    commands.assign(1, Some("none".to_string()));
+    assert_eq!(Storage::new(test_file).load_tasks()[0].assignee, None);
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_assign_without_user_does_nothing() {
+    let test_file = "test_assignee_no_user.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.add("Orphan".to_string());
+    commands.assign(1, None);
+    commands.list_with_options(ListOptions { mine: true, ..ListOptions::default() });
+
+    assert_eq!(Storage::new(test_file).load_tasks()[0].assignee, None);
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_list_assignee_filters() {
+    let test_file = "test_assignee_list.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(Storage::new(test_file)).with_user(Some("me".to_string()));
+    commands.add("Mine".to_string());
+    commands.add("Theirs".to_string());
+    commands.assign(1, None);
+    commands.assign(2, Some("sam".to_string()));
+
+    commands.list_with_options(ListOptions { mine: true, ..ListOptions::default() });
+    commands.list_with_options(ListOptions { assignee: Some("SAM".to_string()), ..ListOptions::default() });
+    commands.list_with_options(ListOptions::default());
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_config_user_name() {
+    let config = Config::parse("[user]\nname = sam\n").unwrap();
+    assert_eq!(config.user, Some("sam".to_string()));
+
+    assert!(Config::parse("[user]\nname = sam lee\n").is_err());
+    assert!(Config::parse("[user]\nemail = sam@example.com\n").is_err());
+}
@@ -0,0 +1,83 @@
+use trackr::commands::Commands;
+use trackr::storage::{Storage, StorageError};
+use trackr::task::Task;
+use std::fs;
+
+fn leftover_temp_files(dir: &str) -> Vec<String> {
+    fs::read_dir(dir).unwrap().flatten()
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| name.ends_with(".tmp"))
+        .collect()
+}
+
+#[test]
+fn test_save_leaves_no_temp_file() {
+    let dir = "test_atomic_dir";
+    fs::remove_dir_all(dir).ok();
+    fs::create_dir(dir).unwrap();
+    let test_file = format!("{}/tasks.json", dir);
+
+    let commands = Commands::new(Storage::new(&test_file));
+    commands.add("First".to_string());
+    commands.add("Second".to_string());
+    commands.mark(1, "done".to_string());
+
+    let tasks = Storage::new(&test_file).load_tasks();
+    assert_eq!(tasks.len(), 2);
+    assert!(leftover_temp_files(dir).is_empty());
+
+    fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn test_failed_write_is_typed_and_keeps_nothing() {
+    let dir = "test_atomic_missing_dir";
+    fs::remove_dir_all(dir).ok();
+    let test_file = format!("{}/tasks.json", dir);
+
+    let error = Storage::new(&test_file).save_tasks(&[Task::new(1, "Nowhere".to_string())]).unwrap_err();
+    assert!(matches!(error, StorageError::Write { ref path, .. } if *path == test_file));
+    assert!(error.to_string().starts_with(&format!("couldn't write {}:", test_file)));
+    assert!(fs::metadata(dir).is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_save_keeps_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let test_file = "test_atomic_permissions.json";
+    fs::remove_file(test_file).ok();
+
+    let storage = Storage::new(test_file);
+    storage.save_tasks(&[Task::new(1, "Private".to_string())]).unwrap();
+    fs::set_permissions(test_file, fs::Permissions::from_mode(0o600)).unwrap();
+
+    storage.save_tasks(&[Task::new(1, "Still private".to_string())]).unwrap();
+    let mode = fs::metadata(test_file).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+    assert_eq!(storage.load_tasks()[0].description, "Still private");
+
+    fs::remove_file(test_file).ok();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_save_through_symlink_keeps_link() {
+    let dir = "test_atomic_symlink";
+    fs::remove_dir_all(dir).ok();
+    fs::create_dir(dir).unwrap();
+    let target = format!("{}/real.json", dir);
+    let link = format!("{}/link.json", dir);
+    std::os::unix::fs::symlink("real.json", &link).unwrap();
+
+    let storage = Storage::new(&link);
+    storage.save_tasks(&[Task::new(1, "Linked".to_string())]).unwrap();
+    storage.save_tasks(&[Task::new(1, "Linked again".to_string())]).unwrap();
+
+    assert!(fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+    assert_eq!(Storage::new(&target).load_tasks()[0].description, "Linked again");
+    assert!(leftover_temp_files(dir).is_empty());
+
+    fs::remove_dir_all(dir).ok();
+}
@@ -0,0 +1,160 @@
+use trackr::commands::{Commands, ListOptions, TaskOptions};
+use trackr::config::{Config, ContextDef};
+use trackr::state::State;
+use trackr::storage::Storage;
+use trackr::task::Task;
+use std::fs;
+
+const CONFIG: &str = "\
+[context work]
+tags = work
+project = acme
+
+[context home]
+tags = home, garden
+";
+
+fn commands(path: &str, state_path: &str) -> Commands {
+    let config = Config::parse(CONFIG).unwrap();
+    Commands::new(Storage::new(path)).with_contexts(config.contexts).with_state(State::new(state_path))
+}
+
+#[test]
+fn test_parse_context_config() {
+    let config = Config::parse(CONFIG).unwrap();
+    assert_eq!(config.contexts, vec![
+        ContextDef { name: "work".to_string(), tags: vec!["work".to_string()], project: Some("acme".to_string()) },
+        ContextDef { name: "home".to_string(), tags: vec!["home".to_string(), "garden".to_string()], project: None },
+    ]);
+
+    assert!(Config::parse("[context empty]\n").is_err());
+    assert!(Config::parse("[context none]\ntags = x\n").is_err());
+    assert!(Config::parse("[context work]\nproject = a..b\n").is_err());
+    assert!(Config::parse("[context work]\ncolor = red\n").is_err());
+}
+
+#[test]
+fn test_context_matches_tag_or_project() {
+    let context = &Config::parse(CONFIG).unwrap().contexts[0];
+
+    let mut task = Task::new(1, "Call".to_string());
+    assert!(!context.matches(&task));
+    task.project = Some("acme.billing".to_string());
+    assert!(context.matches(&task));
+    task.project = None;
+    task.add_tag("work");
+    assert!(context.matches(&task));
+}
+
+#[test]
+fn test_set_context_persists() {
+    let test_file = "test_context_persists.json";
+    let state_file = "test_context_persists.state";
+    fs::remove_file(test_file).ok();
+    fs::remove_file(state_file).ok();
+
+    let commands = commands(test_file, state_file);
+    commands.add("Something".to_string());
+    assert_eq!(State::new(state_file).context(), None);
+
+    commands.set_context(Some("Work"));
+    assert_eq!(State::new(state_file).context(), Some("work".to_string()));
+
+    // Saving tasks and resetting leave the context alone, and it never
+    // ends up in the task file.
+    commands.add("Another".to_string());
+    Storage::new(test_file).clear().unwrap();
+    assert_eq!(State::new(state_file).context(), Some("work".to_string()));
+    assert!(!fs::read_to_string(test_file).unwrap().contains("\"context\""));
+
+    commands.set_context(Some("gym"));
+    assert_eq!(State::new(state_file).context(), Some("work".to_string()));
+
+    commands.set_context(None);
+    assert_eq!(State::new(state_file).context(), None);
+
+    commands.list_contexts();
+
+    fs::remove_file(test_file).ok();
+    fs::remove_file(state_file).ok();
+}
+
+#[test]
+fn test_context_is_per_user() {
+    let test_file = "test_context_per_user.json";
+    let (sam_state, alex_state) = ("test_context_sam.state", "test_context_alex.state");
+    fs::remove_file(test_file).ok();
+    fs::remove_file(sam_state).ok();
+    fs::remove_file(alex_state).ok();
+
+    // Two people sharing one task file each keep their own context.
+    let sam = commands(test_file, sam_state);
+    let alex = commands(test_file, alex_state);
+    sam.set_context(Some("work"));
+    alex.set_context(Some("home"));
+    sam.add("Invoice".to_string());
+    alex.add("Water plants".to_string());
+
+    assert_eq!(State::new(sam_state).context(), Some("work".to_string()));
+    assert_eq!(State::new(alex_state).context(), Some("home".to_string()));
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks[0].tags, vec!["work".to_string()]);
+    assert_eq!(tasks[1].tags, vec!["home".to_string(), "garden".to_string()]);
+
+    fs::remove_file(test_file).ok();
+    fs::remove_file(sam_state).ok();
+    fs::remove_file(alex_state).ok();
+}
+
+#[test]
+fn test_add_inherits_context() {
+    let test_file = "test_context_add.json";
+    let state_file = "test_context_add.state";
+    fs::remove_file(test_file).ok();
+    fs::remove_file(state_file).ok();
+
+    let commands = commands(test_file, state_file);
+    commands.set_context(Some("work"));
+    commands.add("Invoice".to_string());
+    commands.add_with_options("Side project".to_string(),
+        TaskOptions { project: Some("lab".to_string()), ..TaskOptions::default() });
+
+    commands.set_context(Some("home"));
+    commands.add("Water plants".to_string());
+
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks[0].tags, vec!["work".to_string()]);
+    assert_eq!(tasks[0].project, Some("acme".to_string()));
+    assert_eq!(tasks[1].tags, vec!["work".to_string()]);
+    assert_eq!(tasks[1].project, Some("lab".to_string()));
+    assert_eq!(tasks[2].tags, vec!["home".to_string(), "garden".to_string()]);
+    assert_eq!(tasks[2].project, None);
+
+    fs::remove_file(test_file).ok();
+    fs::remove_file(state_file).ok();
+}
+
+#[test]
+fn test_read_commands_apply_context() {
+    let test_file = "test_context_read.json";
+    let state_file = "test_context_read.state";
+    fs::remove_file(test_file).ok();
+    fs::remove_file(state_file).ok();
+
+    let commands = commands(test_file, state_file);
+    commands.add_with_options("Work thing".to_string(),
+        TaskOptions { tags: vec!["work".to_string()], ..TaskOptions::default() });
+    commands.add("Errand".to_string());
+
+    commands.set_context(Some("home"));
+    commands.list_with_options(ListOptions::default());
+    commands.next();
+    commands.tags();
+    commands.projects();
+
+    // Commands without the config ignore the stored context.
+    Commands::new(Storage::new(test_file)).with_state(State::new(state_file)).list_with_options(ListOptions::default());
+
+    fs::remove_file(test_file).ok();
+    fs::remove_file(state_file).ok();
+}
@@ -0,0 +1,123 @@
+use trackr::commands::{ChildAction, Commands, ListOptions};
+use trackr::storage::Storage;
+use trackr::task::{depends_transitively, is_ready, open_blockers, Task, TaskStatus};
+use std::fs;
+
+fn blocked_by(id: u32, deps: &[u32]) -> Task {
+    let mut task = Task::new(id, format!("Task {}", id));
+    task.depends_on = deps.to_vec();
+    task
+}
+
+#[test]
+fn test_open_blockers_and_ready() {
+    let tasks = vec![
+        Task::with_status(3, "Done dep".to_string(), TaskStatus::Done),
+        Task::new(5, "Open dep".to_string()),
+        blocked_by(7, &[3, 5]),
+        blocked_by(8, &[3, 99]),
+    ];
+
+    assert_eq!(open_blockers(&tasks, &tasks[2]), vec![5]);
+    assert!(!is_ready(&tasks, &tasks[2]));
+    assert!(is_ready(&tasks, &tasks[3]));
+    assert!(is_ready(&tasks, &tasks[1]));
+    assert!(!is_ready(&tasks, &tasks[0]));
+}
+
+#[test]
+fn test_depends_transitively() {
+    let tasks = vec![blocked_by(1, &[2]), blocked_by(2, &[3]), Task::new(3, "Leaf".to_string())];
+
+    assert!(depends_transitively(&tasks, 1, 2));
+    assert!(depends_transitively(&tasks, 1, 3));
+    assert!(!depends_transitively(&tasks, 3, 1));
+    assert!(!depends_transitively(&tasks, 2, 1));
+}
+
+#[test]
+fn test_depend_and_undepend_commands() {
+    let test_file = "test_dependencies_commands.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(Storage::new(test_file));
+    for name in ["Design", "Build", "Ship"] {
+        commands.add(name.to_string());
+    }
+
+    commands.depend(3, 2);
+    commands.depend(3, 1);
+    commands.depend(3, 1);
+    commands.depend(3, 42);
+
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks[2].depends_on, vec![2, 1]);
+
+    let contents = fs::read_to_string(test_file).unwrap();
+    assert!(contents.contains("\"depends_on\": [2, 1]"));
+    assert!(contents.contains("\"depends_on\": []"));
+
+    commands.undepend(3, 2);
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks[2].depends_on, vec![1]);
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_depend_rejects_cycles() {
+    let test_file = "test_dependencies_cycles.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.add("A".to_string());
+    commands.add("B".to_string());
+    commands.add("C".to_string());
+
+    commands.depend(1, 2);
+    commands.depend(2, 3);
+    commands.depend(3, 1);
+    commands.depend(1, 1);
+
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks[0].depends_on, vec![2]);
+    assert_eq!(tasks[1].depends_on, vec![3]);
+    assert!(tasks[2].depends_on.is_empty());
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_deleted_dependency_counts_as_satisfied() {
+    let test_file = "test_dependencies_delete.json";
+    let storage = Storage::new(test_file);
+    storage.save_tasks(&[Task::new(1, "Dep".to_string()), blocked_by(2, &[1])]).unwrap();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.delete_with_children(1, Some(ChildAction::Remove));
+
+    // The link stays so a restore puts it back.
+    let tasks = storage.load_tasks();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].depends_on, vec![1]);
+    assert!(is_ready(&tasks, &tasks[0]));
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_ready_list_and_blocked_start() {
+    let test_file = "test_dependencies_ready.json";
+    let storage = Storage::new(test_file);
+    storage.save_tasks(&[Task::new(1, "Dep".to_string()), blocked_by(2, &[1])]).unwrap();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.list_with_options(ListOptions { ready: true, ..ListOptions::default() });
+    commands.mark(2, "in-progress".to_string());
+    commands.show(2);
+
+    let tasks = storage.load_tasks();
+    assert_eq!(tasks[1].status, TaskStatus::InProgress);
+
+    fs::remove_file(test_file).ok();
+}
@@ -0,0 +1,143 @@
+use trackr::commands::{Commands, ListOptions, TaskOptions};
+use trackr::datetime::Date;
+use trackr::storage::Storage;
+use trackr::task::{Task, TaskStatus};
+use std::fs;
+
+fn due(date: &str) -> TaskOptions {
+    TaskOptions {
+        due: Some(date.to_string()),
+        ..TaskOptions::default()
+    }
+}
+
+#[test]
+fn test_date_parse_and_display() {
+    let date = Date::parse("2026-11-01").unwrap();
+    assert_eq!(date, Date { year: 2026, month: 11, day: 1 });
+    assert_eq!(date.to_string(), "2026-11-01");
+
+    assert_eq!(Date::parse("2026-02-30"), None);
+    assert_eq!(Date::parse("2026-13-01"), None);
+    assert_eq!(Date::parse("next week"), None);
+    assert!(Date::parse("2024-02-29").is_some());
+}
+
+#[test]
+fn test_date_ordering_and_arithmetic() {
+    let date = Date::parse("2026-12-31").unwrap();
+    assert_eq!(date.add_days(1), Date::parse("2027-01-01").unwrap());
+    assert!(date < date.add_days(1));
+    assert_eq!(Date::from_timestamp(0), Date::parse("1970-01-01").unwrap());
+}
+
+#[test]
+fn test_overdue_and_due_today() {
+    let today = Date::parse("2026-10-18").unwrap();
+
+    let mut task = Task::new(1, "Deadline".to_string());
+    assert!(!task.is_overdue(today));
+
+    task.due = Some(today.add_days(-1));
+    assert!(task.is_overdue(today));
+    assert!(!task.is_due_today(today));
+
+    task.due = Some(today);
+    assert!(!task.is_overdue(today));
+    assert!(task.is_due_today(today));
+
+    task.due = Some(today.add_days(-3));
+    task.status = TaskStatus::Done;
+    assert!(!task.is_overdue(today));
+}
+
+#[test]
+fn test_add_and_update_due_date() {
+    let test_file = "test_due_add_update.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.add_with_options("Submit report".to_string(), due("2026-11-01"));
+
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks[0].due, Date::parse("2026-11-01"));
+
+    commands.update_with_options(1, None, due("2026-11-15"));
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks[0].due, Date::parse("2026-11-15"));
+    assert_eq!(tasks[0].description, "Submit report");
+
+    commands.update_with_options(1, None, due("none"));
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks[0].due, None);
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_invalid_due_date_is_rejected() {
+    let test_file = "test_due_invalid.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.add_with_options("Bad date".to_string(), due("tomorrow-ish"));
+    assert_eq!(Storage::new(test_file).load_tasks().len(), 0);
+
+    commands.add("Good task".to_string());
+    commands.update_with_options(1, Some("Changed".to_string()), due("2026-02-31"));
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks[0].description, "Good task");
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_due_date_round_trip() {
+    let test_file = "test_due_round_trip.json";
+    let storage = Storage::new(test_file);
+
+    let mut task = Task::new(1, "Dated".to_string());
+    task.due = Date::parse("2026-11-01");
+    storage.save_tasks(&[task, Task::new(2, "Undated".to_string())]).unwrap();
+
+    let contents = fs::read_to_string(test_file).unwrap();
+    assert!(contents.contains("\"due\": \"2026-11-01\""));
+    assert!(contents.contains("\"due\": null"));
+
+    let loaded = storage.load_tasks();
+    assert_eq!(loaded[0].due, Date::parse("2026-11-01"));
+    assert_eq!(loaded[1].due, None);
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_list_with_due_filters() {
+    let test_file = "test_due_list_filters.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.add_with_options("Past".to_string(), due("2020-01-01"));
+    commands.add_with_options("Future".to_string(), due("2999-01-01"));
+
+    commands.list_with_options(ListOptions { overdue: true, ..ListOptions::default() });
+    commands.list_with_options(ListOptions {
+        due_before: Some("2030-01-01".to_string()),
+        ..ListOptions::default()
+    });
+    commands.list_with_options(ListOptions {
+        due_before: Some("soon".to_string()),
+        ..ListOptions::default()
+    });
+
+    fs::remove_file(test_file).ok();
+}
+
+#[cfg(all(unix, target_pointer_width = "64"))]
+#[test]
+fn test_today_is_the_local_date() {
+    // `date` reads the same TZ, so the two agree wherever the tests run.
+    let output = std::process::Command::new("date").arg("+%Y-%m-%d").output().unwrap();
+    let local = Date::parse(String::from_utf8_lossy(&output.stdout).trim()).unwrap();
+    assert_eq!(Date::today(), local);
+}
@@ -0,0 +1,70 @@
+use trackr::commands::Commands;
+use trackr::config::Workflow;
+use trackr::storage::Storage;
+use trackr::task::{StatusChange, Task, TaskStatus};
+use std::fs;
+
+#[test]
+fn test_set_status_records_changes() {
+    let mut task = Task::new(1, "Ship it".to_string());
+    task.set_status(TaskStatus::InProgress, 100);
+    task.set_status(TaskStatus::InProgress, 150);
+    task.set_status(TaskStatus::Done, 200);
+
+    assert_eq!(task.history, vec![
+        StatusChange { at: 100, from: TaskStatus::Todo, to: TaskStatus::InProgress },
+        StatusChange { at: 200, from: TaskStatus::InProgress, to: TaskStatus::Done },
+    ]);
+}
+
+#[test]
+fn test_history_round_trip() {
+    let test_file = "test_history_round_trip.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.add("Review PR".to_string());
+    commands.add("Untouched".to_string());
+    commands.start(1);
+    commands.mark(1, "done".to_string());
+    commands.mark(1, "todo".to_string());
+
+    let tasks = Storage::new(test_file).load_tasks();
+    let steps: Vec<(TaskStatus, TaskStatus)> = tasks[0].history.iter()
+        .map(|change| (change.from.clone(), change.to.clone()))
+        .collect();
+    assert_eq!(steps, vec![
+        (TaskStatus::Todo, TaskStatus::InProgress),
+        (TaskStatus::InProgress, TaskStatus::Done),
+        (TaskStatus::Done, TaskStatus::Todo),
+    ]);
+    assert!(tasks[1].history.is_empty());
+
+    let contents = fs::read_to_string(test_file).unwrap();
+    assert!(contents.contains("\"from\": \"in-progress\", \"to\": \"done\""));
+    assert!(contents.contains("\"history\": []"));
+
+    commands.show(1);
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_history_keeps_removed_custom_statuses() {
+    let test_file = "test_history_custom_status.json";
+    fs::remove_file(test_file).ok();
+
+    let workflow = Workflow::parse("[status blocked]\n").unwrap();
+    let commands = Commands::new(Storage::with_workflow(test_file, workflow));
+    commands.add("Waiting on vendor".to_string());
+    commands.mark(1, "blocked".to_string());
+    commands.mark(1, "todo".to_string());
+
+    // Loading without the custom status still keeps its history entries.
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].history.len(), 2);
+    assert_eq!(tasks[0].history[0].to.to_string(), "blocked");
+
+    fs::remove_file(test_file).ok();
+}
@@ -0,0 +1,145 @@
+use trackr::commands::Commands;
+use trackr::storage::Storage;
+use trackr::task::Task;
+use std::fs::{self, File};
+use std::io::Write;
+
+#[test]
+fn test_deleted_ids_are_not_reused() {
+    let test_file = "test_id_counter_delete.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.add("First".to_string());
+    commands.add("Second".to_string());
+    commands.delete(2);
+    commands.add("Third".to_string());
+
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks.iter().map(|t| t.id).collect::<Vec<_>>(), vec![1, 3]);
+
+    commands.delete(3);
+    commands.delete(1);
+    commands.add("Fourth".to_string());
+    assert_eq!(Storage::new(test_file).load_tasks()[0].id, 4);
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_reset_keeps_the_counter() {
+    let test_file = "test_id_counter_reset.json";
+    fs::remove_file(test_file).ok();
+
+    let storage = Storage::new(test_file);
+    storage.save_with_archive(&[Task::new(1, "Active".to_string())], &[Task::new(6, "Archived".to_string())]).unwrap();
+    storage.clear().unwrap();
+    assert_eq!(storage.next_id(&[]), Some(7));
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.add("After reset".to_string());
+    assert_eq!(Storage::new(test_file).load_tasks()[0].id, 7);
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_next_id_is_persisted() {
+    let test_file = "test_id_counter_persist.json";
+    fs::remove_file(test_file).ok();
+
+    let storage = Storage::new(test_file);
+    assert_eq!(storage.next_id(&[]), Some(1));
+
+    storage.save_tasks(&[Task::new(7, "Seven".to_string())]).unwrap();
+    let contents = fs::read_to_string(test_file).unwrap();
+    assert!(contents.contains("\"next_id\": 8"));
+
+    storage.save_tasks(&[]).unwrap();
+    assert_eq!(storage.next_id(&[]), Some(8));
+    assert!(storage.load_tasks().is_empty());
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_bare_array_file_is_migrated() {
+    let test_file = "test_id_counter_legacy.json";
+    let json = r#"[
+  {
+    "id": 3,
+    "description": "Old task",
+    "status": "todo"
+  }
+]"#;
+    let mut file = File::create(test_file).unwrap();
+    file.write_all(json.as_bytes()).unwrap();
+    drop(file);
+
+    let storage = Storage::new(test_file);
+    let tasks = storage.load_tasks();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(storage.next_id(&tasks), Some(4));
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.add("New task".to_string());
+
+    let contents = fs::read_to_string(test_file).unwrap();
+    assert!(contents.trim_start().starts_with('{'));
+    assert!(contents.contains("\"next_id\": 5"));
+
+    let tasks = storage.load_tasks();
+    assert_eq!(tasks.iter().map(|t| t.id).collect::<Vec<_>>(), vec![3, 4]);
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_counter_never_lags_behind_tasks() {
+    let test_file = "test_id_counter_lagging.json";
+    let json = r#"{
+  "next_id": 2,
+  "tasks": [
+    {
+      "id": 9,
+      "description": "Hand-edited",
+      "status": "todo"
+    }
+  ]
+}"#;
+    let mut file = File::create(test_file).unwrap();
+    file.write_all(json.as_bytes()).unwrap();
+    drop(file);
+
+    let storage = Storage::new(test_file);
+    let tasks = storage.load_tasks();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(storage.next_id(&tasks), Some(10));
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_running_out_of_ids() {
+    let test_file = "test_id_counter_exhausted.json";
+    fs::write(test_file, r#"[{"id": 4294967295, "description": "Last", "status": "todo", "recurrence": "daily"}]"#).unwrap();
+
+    let storage = Storage::new(test_file);
+    assert_eq!(storage.next_id(&storage.load_tasks()), None);
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.add("One too many".to_string());
+    assert_eq!(storage.load_tasks().len(), 1);
+
+    // Finishing it still saves, and the counter remembers that every id is used.
+    commands.mark(4294967295, "done".to_string());
+    let tasks = storage.load_tasks();
+    assert_eq!(tasks.len(), 1);
+    assert!(tasks[0].status.is_closed());
+    assert!(fs::read_to_string(test_file).unwrap().contains("\"next_id\": 4294967296"));
+
+    storage.save_tasks(&[]).unwrap();
+    assert_eq!(storage.next_id(&[]), None);
+
+    fs::remove_file(test_file).ok();
+}
@@ -0,0 +1,122 @@
+use trackr::json::{self, Value};
+use trackr::storage::Storage;
+use std::fs;
+
+fn error_at(input: &str) -> (usize, usize) {
+    let error = json::parse(input).unwrap_err();
+    (error.position.line, error.position.column)
+}
+
+#[test]
+fn test_parse_values() {
+    let document = json::parse(r#" {"a": [1, -2.5e2, true, false, null], "b": {"c": "d"}} "#).unwrap();
+    let items = document.get("a").unwrap().as_array().unwrap();
+    assert_eq!(items[0].as_u32(), Some(1));
+    assert_eq!(items[1].as_f64(), Some(-250.0));
+    assert_eq!(items[2].value, Value::Bool(true));
+    assert_eq!(items[3].value, Value::Bool(false));
+    assert!(items[4].is_null());
+    assert_eq!(document.get("b").unwrap().get("c").unwrap().as_str(), Some("d"));
+    assert_eq!(document.get("missing"), None);
+}
+
+#[test]
+fn test_parse_string_escapes() {
+    let document = json::parse(r#""tab\t quote\" slash\/ é 😀 {braces}""#).unwrap();
+    assert_eq!(document.as_str(), Some("tab\t quote\" slash/ é 😀 {braces}"));
+
+    // A lone surrogate can't be a char, so it is replaced.
+    assert_eq!(json::parse(r#""\ud800x""#).unwrap().as_str(), Some("\u{FFFD}x"));
+}
+
+#[test]
+fn test_as_u32_rejects_fractions_and_strings() {
+    assert_eq!(json::parse("12").unwrap().as_u32(), Some(12));
+    assert_eq!(json::parse("1.5").unwrap().as_u32(), None);
+    assert_eq!(json::parse("-1").unwrap().as_u32(), None);
+    assert_eq!(json::parse(r#""12abc""#).unwrap().as_u32(), None);
+}
+
+#[test]
+fn test_errors_have_line_and_column() {
+    assert_eq!(error_at("{\n  \"a\": 1\n  \"b\": 2\n}"), (3, 3));
+    assert_eq!(error_at("[1, 2,]"), (1, 7));
+    assert_eq!(error_at("[01]"), (1, 3));
+    assert_eq!(error_at("{\"a\" 1}"), (1, 6));
+    assert_eq!(error_at("\"unterminated"), (1, 14));
+    assert_eq!(error_at("\"bad \\x escape\""), (1, 6));
+    assert_eq!(error_at("[1] [2]"), (1, 5));
+    assert_eq!(error_at("tru"), (1, 1));
+    assert_eq!(error_at(""), (1, 1));
+
+    let error = json::parse("[\n  nope\n]").unwrap_err();
+    assert_eq!(error.to_string(), "line 2, column 3: expected a value");
+}
+
+#[test]
+fn test_columns_count_characters() {
+    assert_eq!(error_at("[\"é😀\", x]"), (1, 8));
+    assert_eq!(error_at("{\"ü\": \"a\u{1}\"}"), (1, 9));
+    assert_eq!(json::parse("[\"é\", ü]").unwrap_err().message, "unexpected character 'ü'");
+}
+
+#[test]
+fn test_span_covers_value() {
+    let input = r#"{"a": [1, {"b": 2}], "c": 3}"#;
+    let document = json::parse(input).unwrap();
+    assert_eq!(&input[document.get("a").unwrap().span()], r#"[1, {"b": 2}]"#);
+}
+
+#[test]
+fn test_deep_nesting_is_an_error() {
+    let input = format!("{}{}", "[".repeat(10_000), "]".repeat(10_000));
+    assert!(json::parse(&input).is_err());
+}
+
+#[test]
+fn test_load_tasks_in_any_layout() {
+    let test_file = "test_json_layout.json";
+    let contents = r#"{"next_id": 3, "tasks": [{"id": 1, "description": "One { line }", "status": "todo", "tags": ["a"]},
+        {"status": "done",
+         "description": "Café \"run\"",
+         "id": 2, "due": null}]}"#;
+    fs::write(test_file, contents).unwrap();
+
+    let storage = Storage::new(test_file);
+    assert_eq!(storage.check(), Ok(()));
+    let tasks = storage.load_tasks();
+    assert_eq!(tasks.len(), 2);
+    assert_eq!(tasks[0].description, "One { line }");
+    assert_eq!(tasks[0].tags, vec!["a".to_string()]);
+    assert_eq!(tasks[1].description, "Café \"run\"");
+    assert_eq!(storage.next_id(&tasks), Some(3));
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_check_reports_bad_tasks() {
+    let test_file = "test_json_check.json";
+    let check = |contents: &str| {
+        fs::write(test_file, contents).unwrap();
+        Storage::new(test_file).check().unwrap_err().to_string()
+    };
+
+    assert_eq!(check("{\"tasks\": [\n  {\"id\": \"12abc\", \"description\": \"x\", \"status\": \"todo\"}\n]}"),
+        "test_json_check.json: line 2, column 10: \"id\" should be a whole number");
+    assert_eq!(check("{\"tasks\": [{\"id\": 1, \"status\": \"todo\"}]}"),
+        "test_json_check.json: line 1, column 12: missing \"description\"");
+    assert_eq!(check("{\"tasks\": [{\"id\": 1, \"description\": \"x\", \"status\": 3}]}"),
+        "test_json_check.json: line 1, column 52: \"status\" should be a string");
+    assert_eq!(check("{\"tasks\": [], \"trash\": [{\"id\": 1, \"description\": \"x\", \"status\": \"todo\", \"due\": \"soon\"}]}"),
+        "test_json_check.json: line 1, column 80: \"due\" should be a date like YYYY-MM-DD");
+    assert_eq!(check("{\"tasks\": {}}"),
+        "test_json_check.json: line 1, column 11: \"tasks\" should be an array, found an object");
+    assert!(check("{\"tasks\": [").starts_with("test_json_check.json: line 1, column 12:"));
+
+    // A broken file loads as empty rather than half-read.
+    assert!(Storage::new(test_file).load_tasks().is_empty());
+
+    fs::remove_file(test_file).ok();
+    assert_eq!(Storage::new(test_file).check(), Ok(()));
+}
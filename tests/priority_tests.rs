@@ -0,0 +1,100 @@
+use trackr::commands::{Commands, ListOptions, TaskOptions};
+use trackr::storage::Storage;
+use trackr::task::{Priority, Task};
+use std::fs;
+
+#[test]
+fn test_priority_from_str() {
+    assert_eq!(Priority::from_str("low"), Some(Priority::Low));
+    assert_eq!(Priority::from_str("Medium"), Some(Priority::Medium));
+    assert_eq!(Priority::from_str("HIGH"), Some(Priority::High));
+    assert_eq!(Priority::from_str("critical"), Some(Priority::Critical));
+    assert_eq!(Priority::from_str("h"), Some(Priority::High));
+    assert_eq!(Priority::from_str("urgent"), None);
+}
+
+#[test]
+fn test_priority_ordering_and_display() {
+    assert!(Priority::Low < Priority::Medium);
+    assert!(Priority::High < Priority::Critical);
+    assert!(None < Some(Priority::Low));
+    assert_eq!(format!("{}", Priority::Critical), "critical");
+}
+
+#[test]
+fn test_add_update_and_prio() {
+    let test_file = "test_priority_commands.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.add_with_options("Fix prod".to_string(), TaskOptions {
+        priority: Some("critical".to_string()),
+        ..TaskOptions::default()
+    });
+    commands.add("Water plants".to_string());
+
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks[0].priority, Some(Priority::Critical));
+    assert_eq!(tasks[1].priority, None);
+
+    commands.prio(2, "low".to_string());
+    commands.update_with_options(1, None, TaskOptions {
+        priority: Some("high".to_string()),
+        ..TaskOptions::default()
+    });
+
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks[0].priority, Some(Priority::High));
+    assert_eq!(tasks[1].priority, Some(Priority::Low));
+
+    commands.prio(2, "none".to_string());
+    commands.prio(1, "whenever".to_string());
+
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks[0].priority, Some(Priority::High));
+    assert_eq!(tasks[1].priority, None);
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_priority_round_trip() {
+    let test_file = "test_priority_round_trip.json";
+    let storage = Storage::new(test_file);
+
+    let mut task = Task::new(1, "Important".to_string());
+    task.priority = Some(Priority::Medium);
+    storage.save_tasks(&[task]).unwrap();
+
+    let contents = fs::read_to_string(test_file).unwrap();
+    assert!(contents.contains("\"priority\": \"medium\""));
+    assert_eq!(storage.load_tasks()[0].priority, Some(Priority::Medium));
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_list_sorted_and_filtered_by_priority() {
+    let test_file = "test_priority_list.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.add("A".to_string());
+    commands.add("B".to_string());
+    commands.prio(2, "high".to_string());
+
+    commands.list_with_options(ListOptions {
+        sort: Some("priority".to_string()),
+        ..ListOptions::default()
+    });
+    commands.list_with_options(ListOptions {
+        priority: Some("high".to_string()),
+        ..ListOptions::default()
+    });
+    commands.list_with_options(ListOptions {
+        sort: Some("vibes".to_string()),
+        ..ListOptions::default()
+    });
+
+    fs::remove_file(test_file).ok();
+}
@@ -0,0 +1,118 @@
+use trackr::commands::{Commands, ListOptions, TaskOptions};
+use trackr::storage::Storage;
+use trackr::task::{project_summary, ProjectSummary, Task, TaskStatus};
+use std::fs;
+
+fn in_project(id: u32, project: &str, status: TaskStatus) -> Task {
+    let mut task = Task::with_status(id, format!("Task {}", id), status);
+    task.project = Some(project.to_string());
+    task
+}
+
+#[test]
+fn test_valid_project_paths() {
+    assert!(Task::is_valid_project("infra"));
+    assert!(Task::is_valid_project("infra.ci.runners"));
+    assert!(!Task::is_valid_project(""));
+    assert!(!Task::is_valid_project("infra..ci"));
+    assert!(!Task::is_valid_project(".infra"));
+    assert!(!Task::is_valid_project("my project"));
+}
+
+#[test]
+fn test_in_project_matches_whole_segments() {
+    let task = in_project(1, "infra.ci.runners", TaskStatus::Todo);
+
+    assert!(task.in_project("infra"));
+    assert!(task.in_project("infra.ci"));
+    assert!(task.in_project("infra.ci.runners"));
+    assert!(!task.in_project("infra.c"));
+    assert!(!task.in_project("infra.ci.runners.linux"));
+    assert!(!Task::new(2, "No project".to_string()).in_project("infra"));
+}
+
+#[test]
+fn test_project_summary_rolls_up() {
+    let tasks = vec![
+        in_project(1, "infra.ci", TaskStatus::Todo),
+        in_project(2, "infra.ci.runners", TaskStatus::InProgress),
+        in_project(3, "infra", TaskStatus::Done),
+        in_project(4, "web", TaskStatus::Todo),
+        Task::new(5, "Loose".to_string()),
+    ];
+
+    let summary = project_summary(&tasks);
+
+    assert_eq!(summary.len(), 4);
+    assert_eq!(summary["infra"], ProjectSummary { todo: 1, in_progress: 1, done: 1 });
+    assert_eq!(summary["infra.ci"], ProjectSummary { todo: 1, in_progress: 1, done: 0 });
+    assert_eq!(summary["infra.ci.runners"], ProjectSummary { todo: 0, in_progress: 1, done: 0 });
+    assert_eq!(summary["web"], ProjectSummary { todo: 1, in_progress: 0, done: 0 });
+}
+
+#[test]
+fn test_project_set_and_cleared_by_commands() {
+    let test_file = "test_projects_commands.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.add_with_options("Upgrade runners".to_string(), TaskOptions {
+        project: Some("infra.ci.runners".to_string()),
+        ..TaskOptions::default()
+    });
+    commands.add_with_options("Bad".to_string(), TaskOptions {
+        project: Some("infra..ci".to_string()),
+        ..TaskOptions::default()
+    });
+
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].project.as_deref(), Some("infra.ci.runners"));
+
+    commands.update_with_options(1, None, TaskOptions {
+        project: Some("none".to_string()),
+        ..TaskOptions::default()
+    });
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks[0].project, None);
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_project_round_trip() {
+    let test_file = "test_projects_round_trip.json";
+    let storage = Storage::new(test_file);
+
+    storage.save_tasks(&[in_project(1, "infra.ci", TaskStatus::Todo)]).unwrap();
+
+    let contents = fs::read_to_string(test_file).unwrap();
+    assert!(contents.contains("\"project\": \"infra.ci\""));
+    assert_eq!(storage.load_tasks()[0].project.as_deref(), Some("infra.ci"));
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_grouped_list_and_projects_summary() {
+    let test_file = "test_projects_list.json";
+    let storage = Storage::new(test_file);
+    storage.save_tasks(&[
+        in_project(1, "web", TaskStatus::Todo),
+        Task::new(2, "Loose".to_string()),
+        in_project(3, "infra.ci", TaskStatus::Done),
+    ]).unwrap();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.list_with_options(ListOptions {
+        group_by_project: true,
+        ..ListOptions::default()
+    });
+    commands.list_with_options(ListOptions {
+        project: Some("infra".to_string()),
+        ..ListOptions::default()
+    });
+    commands.projects();
+
+    fs::remove_file(test_file).ok();
+}
@@ -0,0 +1,111 @@
+use trackr::commands::Commands;
+use trackr::storage::{Storage, StorageError};
+use trackr::task::Task;
+use std::fs;
+
+const BROKEN: &str = r#"{
+  "next_id": 7,
+  "tasks": [
+    {"id": 1, "description": "Keep me", "status": "todo"},
+    {"id": "two", "description": "Bad id", "status": "todo"},
+    {"id": 3, "description": "Keep me too", "status": "done"}
+  ],
+  "trash": [{"id": "x"}]
+}"#;
+
+fn remove_backups(test_file: &str) {
+    for entry in fs::read_dir(".").unwrap().flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with(&format!("{}.broken-", test_file)) {
+            fs::remove_file(name).ok();
+        }
+    }
+}
+
+#[test]
+fn test_read_errors_are_typed() {
+    let test_file = "test_recovery_typed.json";
+    fs::remove_file(test_file).ok();
+    let storage = Storage::new(test_file);
+
+    assert_eq!(storage.read_tasks().unwrap_err(), StorageError::Missing { path: test_file.to_string() });
+    assert!(storage.load_tasks().is_empty());
+
+    fs::write(test_file, "{\"tasks\": [").unwrap();
+    assert!(matches!(storage.read_tasks(), Err(StorageError::Corrupt { .. })));
+    assert!(matches!(storage.check(), Err(StorageError::Corrupt { .. })));
+
+    fs::write(test_file, [0xff, 0xfe, 0x00]).unwrap();
+    assert!(matches!(storage.read_tasks(), Err(StorageError::Unreadable { .. })));
+
+    fs::write(test_file, "  \n").unwrap();
+    assert!(storage.read_tasks().unwrap().is_empty());
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_corrupt_file_is_never_overwritten() {
+    let test_file = "test_recovery_refuse.json";
+    fs::write(test_file, BROKEN).unwrap();
+
+    let storage = Storage::new(test_file);
+    let error = storage.save_tasks(&[Task::new(1, "Clobber".to_string())]).unwrap_err();
+    assert!(matches!(error, StorageError::Corrupt { .. }));
+    assert!(error.to_string().contains("\"id\" should be a whole number"));
+    assert!(storage.clear().is_err());
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.add("New task".to_string());
+    commands.mark(1, "done".to_string());
+    commands.delete(1);
+    commands.reset();
+
+    assert_eq!(fs::read_to_string(test_file).unwrap(), BROKEN);
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_recover_keeps_readable_tasks() {
+    let test_file = "test_recovery_salvage.json";
+    remove_backups(test_file);
+    fs::write(test_file, BROKEN).unwrap();
+
+    let storage = Storage::new(test_file);
+    let recovery = storage.recover().unwrap().unwrap();
+    assert!(recovery.parsed);
+    assert_eq!(recovery.kept, 2);
+    assert_eq!(recovery.dropped, 2);
+    assert_eq!(fs::read_to_string(&recovery.backup_path).unwrap(), BROKEN);
+
+    assert_eq!(storage.check(), Ok(()));
+    let tasks = storage.load_tasks();
+    assert_eq!(tasks.len(), 2);
+    assert_eq!(tasks[0].description, "Keep me");
+    assert_eq!(tasks[1].description, "Keep me too");
+    assert_eq!(storage.next_id(&tasks), Some(7));
+
+    assert_eq!(storage.recover(), Ok(None));
+
+    fs::remove_file(&recovery.backup_path).ok();
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_recover_invalid_json_starts_fresh() {
+    let test_file = "test_recovery_fresh.json";
+    remove_backups(test_file);
+    fs::write(test_file, "[{\"id\": 1, \"desc").unwrap();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.recover();
+    commands.add("After recovery".to_string());
+
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].description, "After recovery");
+
+    remove_backups(test_file);
+    fs::remove_file(test_file).ok();
+}
@@ -0,0 +1,155 @@
+use trackr::commands::{Commands, TaskOptions};
+use trackr::datetime::Date;
+use trackr::storage::Storage;
+use trackr::task::{Recurrence, Task, TaskStatus, UdaValue};
+use std::fs;
+
+fn date(s: &str) -> Date {
+    Date::parse(s).unwrap()
+}
+
+#[test]
+fn test_recurrence_parse_and_format() {
+    assert_eq!(Recurrence::from_str("daily"), Some(Recurrence::Daily));
+    assert_eq!(Recurrence::from_str("Monthly"), Some(Recurrence::Monthly));
+    assert_eq!(Recurrence::from_str("monthly:31"), Some(Recurrence::MonthlyOn(31)));
+    assert_eq!(Recurrence::from_str("monthly:32"), None);
+    assert_eq!(Recurrence::from_str("every:3"), Some(Recurrence::EveryDays(3)));
+    assert_eq!(Recurrence::from_str("weekly:thu,mon"), Some(Recurrence::Weekly(vec![0, 3])));
+    assert_eq!(Recurrence::from_str("weekly"), Some(Recurrence::Weekly(Vec::new())));
+    assert_eq!(Recurrence::from_str("every:0"), None);
+    assert_eq!(Recurrence::from_str("weekly:funday"), None);
+    assert_eq!(Recurrence::from_str("hourly"), None);
+
+    for rule in ["daily", "weekly", "weekly:mon,thu", "monthly", "monthly:15", "every:10"] {
+        assert_eq!(Recurrence::from_str(rule).unwrap().to_string(), rule);
+    }
+}
+
+#[test]
+fn test_recurrence_next_after() {
+    // 2024-01-01 was a Monday.
+    assert_eq!(date("2024-01-01").weekday(), 0);
+    assert_eq!(Recurrence::Daily.next_after(date("2024-12-31")), date("2025-01-01"));
+    assert_eq!(Recurrence::EveryDays(10).next_after(date("2024-01-01")), date("2024-01-11"));
+    assert_eq!(Recurrence::Monthly.next_after(date("2024-01-31")), date("2024-02-29"));
+    assert_eq!(Recurrence::Monthly.next_after(date("2024-12-15")), date("2025-01-15"));
+    assert_eq!(Recurrence::MonthlyOn(31).next_after(date("2024-02-29")), date("2024-03-31"));
+    assert_eq!(Recurrence::MonthlyOn(15).next_after(date("2024-01-03")), date("2024-01-15"));
+    assert_eq!(Recurrence::MonthlyOn(15).next_after(date("2024-01-15")), date("2024-02-15"));
+    assert_eq!(Recurrence::Weekly(Vec::new()).next_after(date("2024-01-01")), date("2024-01-08"));
+
+    let mon_thu = Recurrence::Weekly(vec![0, 3]);
+    assert_eq!(mon_thu.next_after(date("2024-01-01")), date("2024-01-04"));
+    assert_eq!(mon_thu.next_after(date("2024-01-04")), date("2024-01-08"));
+}
+
+#[test]
+fn test_monthly_series_keeps_its_day() {
+    let mut task = Task::new(1, "Pay rent".to_string());
+    task.recurrence = Some(Recurrence::Monthly);
+    task.due = Some(date("2027-01-31"));
+
+    let mut dues = Vec::new();
+    for id in 2..6 {
+        task = task.next_occurrence(id, date("2027-01-01"), 100).unwrap();
+        dues.push(task.due.unwrap().to_string());
+    }
+    assert_eq!(dues, vec!["2027-02-28", "2027-03-31", "2027-04-30", "2027-05-31"]);
+    assert_eq!(task.recurrence, Some(Recurrence::MonthlyOn(31)));
+}
+
+#[test]
+fn test_next_occurrence_skips_past_dates() {
+    let mut task = Task::new(4, "Water plants".to_string());
+    task.recurrence = Some(Recurrence::Daily);
+    task.due = Some(date("2024-01-01"));
+    task.tags = vec!["home".to_string()];
+
+    let next = task.next_occurrence(9, date("2024-01-10"), 100).unwrap();
+    assert_eq!(next.id, 9);
+    assert_eq!(next.status, TaskStatus::Todo);
+    assert_eq!(next.due, Some(date("2024-01-10")));
+    assert_eq!(next.tags, task.tags);
+    assert_eq!(next.recur_from, Some(4));
+
+    let plain = Task::new(5, "One-off".to_string());
+    assert!(plain.next_occurrence(9, date("2024-01-10"), 100).is_none());
+}
+
+#[test]
+fn test_next_occurrence_keeps_its_details() {
+    let mut task = Task::new(4, "On-call".to_string());
+    task.recurrence = Some(Recurrence::Daily);
+    task.assignee = Some("bob".to_string());
+    task.udas.insert("points".to_string(), UdaValue::Number(9.0));
+    task.udas.insert("ticket".to_string(), UdaValue::String("OPS-7".to_string()));
+
+    let next = task.next_occurrence(9, date("2024-01-10"), 100).unwrap();
+    assert_eq!(next.assignee, Some("bob".to_string()));
+    assert_eq!(next.udas, task.udas);
+}
+
+#[test]
+fn test_marking_recurring_task_done_spawns_next() {
+    let test_file = "test_recurrence_spawn.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(Storage::new(test_file));
+    let options = TaskOptions {
+        recur: Some("weekly".to_string()),
+        due: Some(Date::today().to_string()),
+        ..TaskOptions::default()
+    };
+    commands.add_with_options("Weekly review".to_string(), options);
+    commands.mark(1, "done".to_string());
+
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks.len(), 2);
+    assert_eq!(tasks[1].description, "Weekly review");
+    assert_eq!(tasks[1].status, TaskStatus::Todo);
+    assert_eq!(tasks[1].due, Some(Date::today().add_days(7)));
+    assert_eq!(tasks[1].recurrence, Some(Recurrence::Weekly(Vec::new())));
+    assert_eq!(tasks[1].recur_from, Some(1));
+
+    // Reopening and finishing the first one again doesn't duplicate the series.
+    commands.mark(1, "todo".to_string());
+    commands.mark(1, "done".to_string());
+    assert_eq!(Storage::new(test_file).load_tasks().len(), 2);
+
+    commands.mark(2, "done".to_string());
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks.len(), 3);
+    assert_eq!(tasks[2].recur_from, Some(1));
+
+    let contents = fs::read_to_string(test_file).unwrap();
+    assert!(contents.contains("\"recurrence\": \"weekly\""));
+    assert!(contents.contains("\"recur_from\": 1"));
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_update_recurrence() {
+    let test_file = "test_recurrence_update.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.add("Pay rent".to_string());
+
+    let recur = |rule: &str| TaskOptions { recur: Some(rule.to_string()), ..TaskOptions::default() };
+    commands.update_with_options(1, None, recur("monthly"));
+    assert_eq!(Storage::new(test_file).load_tasks()[0].recurrence, Some(Recurrence::Monthly));
+
+    commands.update_with_options(1, None, recur("fortnightly"));
+    assert_eq!(Storage::new(test_file).load_tasks()[0].recurrence, Some(Recurrence::Monthly));
+
+    commands.update_with_options(1, None, recur("none"));
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks[0].recurrence, None);
+
+    commands.mark(1, "done".to_string());
+    assert_eq!(Storage::new(test_file).load_tasks().len(), 1);
+
+    fs::remove_file(test_file).ok();
+}
@@ -1,5 +1,6 @@
-use trackr::commands::Commands;
+use trackr::commands::{Commands, ResetOptions, TaskOptions};
 use trackr::storage::Storage;
+use trackr::task::{Task, TaskStatus};
 use std::fs;
 
 #[test]
@@ -54,7 +55,7 @@ fn test_reset_then_add_new_task() {
     let loaded_storage = Storage::new(test_file);
     let tasks = loaded_storage.load_tasks();
     assert_eq!(tasks.len(), 1);
-    assert_eq!(tasks[0].id, 1);
+    assert_eq!(tasks[0].id, 3);
     assert_eq!(tasks[0].description, "New Task");
 
     fs::remove_file(test_file).ok();
@@ -118,9 +119,131 @@ fn test_reset_preserves_file_structure() {
     assert!(std::path::Path::new(test_file).exists());
 
     let content = fs::read_to_string(test_file).unwrap();
-    let trimmed = content.trim();
-    assert!(trimmed == "[]" || trimmed == "[\n]");
+    assert!(content.contains("\"tasks\": []"));
+    assert!(content.contains("\"next_id\": 2"));
 
     fs::remove_file(test_file).ok();
 }
 
+
+fn tagged(tag: &str) -> TaskOptions {
+    TaskOptions { tags: vec![tag.to_string()], ..TaskOptions::default() }
+}
+
+#[test]
+fn test_reset_dry_run_keeps_everything() {
+    let test_file = "test_reset_dry_run.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.add("Task 1".to_string());
+    commands.add("Task 2".to_string());
+    commands.mark(1, "done".to_string());
+
+    commands.reset_with(ResetOptions { dry_run: true, ..ResetOptions::default() });
+    commands.reset_with(ResetOptions {
+        dry_run: true,
+        status: Some("done".to_string()),
+        ..ResetOptions::default()
+    });
+
+    assert_eq!(Storage::new(test_file).load_tasks().len(), 2);
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_reset_by_status() {
+    let test_file = "test_reset_by_status.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(Storage::new(test_file));
+    for name in ["One", "Two", "Three"] {
+        commands.add(name.to_string());
+    }
+    commands.mark(1, "done".to_string());
+    commands.mark(3, "done".to_string());
+    commands.depend(2, 1);
+
+    commands.reset_with(ResetOptions {
+        yes: true,
+        status: Some("done".to_string()),
+        ..ResetOptions::default()
+    });
+
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].id, 2);
+    assert!(tasks[0].depends_on.is_empty());
+
+    // A scoped reset keeps the id counter going.
+    commands.add("Four".to_string());
+    assert_eq!(Storage::new(test_file).load_tasks()[1].id, 4);
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_reset_by_tag_reparents_subtasks() {
+    let test_file = "test_reset_by_tag.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.add("Epic".to_string());
+    commands.add_with_options("Sprint item".to_string(), TaskOptions {
+        parent: Some("1".to_string()),
+        ..tagged("sprint-12")
+    });
+    commands.add_with_options("Detail".to_string(), TaskOptions {
+        parent: Some("2".to_string()),
+        ..TaskOptions::default()
+    });
+    commands.add_with_options("Other sprint".to_string(), tagged("sprint-13"));
+
+    commands.reset_with(ResetOptions {
+        yes: true,
+        tags: vec!["sprint-12".to_string()],
+        ..ResetOptions::default()
+    });
+
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks.iter().map(|t| t.id).collect::<Vec<_>>(), vec![1, 3, 4]);
+    assert_eq!(tasks[1].parent, Some(1));
+
+    commands.reset_with(ResetOptions {
+        yes: true,
+        status: Some("bogus".to_string()),
+        ..ResetOptions::default()
+    });
+    assert_eq!(Storage::new(test_file).load_tasks().len(), 3);
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_scoped_reset_survives_parent_cycles() {
+    let test_file = "test_reset_parent_cycle.json";
+    let child = |id: u32, parent: u32, status: TaskStatus| {
+        let mut task = Task::with_status(id, format!("Task {}", id), status);
+        task.parent = Some(parent);
+        task
+    };
+    Storage::new(test_file).save_tasks(&[
+        child(1, 2, TaskStatus::Done),
+        child(2, 1, TaskStatus::Done),
+        child(3, 1, TaskStatus::Todo),
+    ]).unwrap();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.reset_with(ResetOptions {
+        yes: true,
+        status: Some("done".to_string()),
+        ..ResetOptions::default()
+    });
+
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].parent, None);
+
+    fs::remove_file(test_file).ok();
+}
@@ -1,5 +1,5 @@
 use trackr::storage::Storage;
-use trackr::task::Task;
+use trackr::task::{Task, TaskStatus};
 use std::fs::{self, File};
 use std::io::Write;
 
@@ -115,7 +115,9 @@ fn test_load_tasks_with_invalid_status() {
     let storage = Storage::new(test_file);
     let tasks = storage.load_tasks();
 
-    assert_eq!(tasks.len(), 0);
+    // A status missing from the config is kept as an open custom one.
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].status, TaskStatus::Custom { name: "invalid-status".to_string(), closed: false });
 
     fs::remove_file(test_file).ok();
 }
@@ -262,3 +264,23 @@ fn test_json_not_ending_with_bracket() {
     fs::remove_file(test_file).ok();
 }
 
+
+#[test]
+fn test_reads_see_changes_made_in_between() {
+    let test_file = "test_reads_see_changes.json";
+    let storage = Storage::new(test_file);
+
+    fs::write(test_file, r#"[{"id": 1, "description": "Old", "status": "todo"}]"#).unwrap();
+    assert_eq!(storage.load_tasks()[0].description, "Old");
+
+    // Same length, written straight after, so only the contents differ.
+    fs::write(test_file, r#"[{"id": 1, "description": "New", "status": "todo"}]"#).unwrap();
+    assert_eq!(storage.load_tasks()[0].description, "New");
+
+    storage.save_tasks(&[Task::new(2, "Saved".to_string())]).unwrap();
+    let tasks = storage.load_tasks();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].description, "Saved");
+
+    fs::remove_file(test_file).ok();
+}
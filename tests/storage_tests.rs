@@ -160,8 +160,10 @@ fn test_json_format_validity() {
     assert!(contents.contains("\"id\": 1"));
     assert!(contents.contains("\"description\": \"Test\""));
     assert!(contents.contains("\"status\": \"todo\""));
-    assert!(contents.starts_with('['));
-    assert!(contents.trim().ends_with(']'));
+    assert!(contents.starts_with('{'));
+    assert!(contents.trim().ends_with('}'));
+    assert!(contents.contains("\"next_id\": 2"));
+    assert!(contents.contains("\"tasks\": ["));
 
     fs::remove_file(test_file).ok();
 }
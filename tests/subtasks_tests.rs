@@ -0,0 +1,189 @@
+use trackr::commands::{ChildAction, Commands, ListOptions, TaskOptions};
+use trackr::storage::Storage;
+use trackr::task::{children_of, descendants_of, tree_order, Task, TaskStatus};
+use std::fs;
+
+fn parent(id: &str) -> TaskOptions {
+    TaskOptions {
+        parent: Some(id.to_string()),
+        ..TaskOptions::default()
+    }
+}
+
+fn child(id: u32, parent: u32) -> Task {
+    let mut task = Task::new(id, format!("Task {}", id));
+    task.parent = Some(parent);
+    task
+}
+
+#[test]
+fn test_children_and_descendants() {
+    let tasks = vec![
+        Task::new(1, "Root".to_string()),
+        child(2, 1),
+        child(3, 1),
+        child(4, 2),
+        child(5, 4),
+        Task::new(6, "Other".to_string()),
+    ];
+
+    assert_eq!(children_of(&tasks, 1), vec![2, 3]);
+    assert_eq!(descendants_of(&tasks, 1), vec![2, 3, 4, 5]);
+    assert_eq!(descendants_of(&tasks, 4), vec![5]);
+    assert!(descendants_of(&tasks, 6).is_empty());
+}
+
+#[test]
+fn test_descendants_tolerates_cycles() {
+    let tasks = vec![child(1, 2), child(2, 1)];
+    assert_eq!(descendants_of(&tasks, 1), vec![2]);
+
+    let tasks = vec![child(1, 1), child(2, 1)];
+    assert_eq!(children_of(&tasks, 1), vec![2]);
+    assert_eq!(descendants_of(&tasks, 1), vec![2]);
+}
+
+#[test]
+fn test_delete_self_parented_task() {
+    let test_file = "test_subtasks_delete_self_parent.json";
+    let storage = Storage::new(test_file);
+    storage.save_tasks(&[child(1, 1), Task::new(2, "Unrelated".to_string())]).unwrap();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.delete_with_children(1, Some(ChildAction::Remove));
+
+    assert_eq!(storage.load_tasks().iter().map(|t| t.id).collect::<Vec<_>>(), vec![2]);
+    assert_eq!(storage.load_trash().len(), 1);
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_tree_order_draws_cycles_as_extra_roots() {
+    let tasks = [Task::new(1, "Root".to_string()), child(2, 1), child(3, 4), child(4, 3), child(5, 4)];
+    let refs: Vec<&Task> = tasks.iter().collect();
+    let order: Vec<(u32, usize)> = tree_order(&refs).iter().map(|(t, depth)| (t.id, *depth)).collect();
+    assert_eq!(order, vec![(1, 0), (2, 1), (3, 0), (4, 1), (5, 2)]);
+}
+
+#[test]
+fn test_add_subtask_and_round_trip() {
+    let test_file = "test_subtasks_add.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.add("Release v2".to_string());
+    commands.add_with_options("Write tests".to_string(), parent("1"));
+    commands.add_with_options("Orphan".to_string(), parent("42"));
+
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks.len(), 2);
+    assert_eq!(tasks[0].parent, None);
+    assert_eq!(tasks[1].parent, Some(1));
+
+    let contents = fs::read_to_string(test_file).unwrap();
+    assert!(contents.contains("\"parent\": 1"));
+    assert!(contents.contains("\"parent\": null"));
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_update_rejects_parent_cycles() {
+    let test_file = "test_subtasks_cycles.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.add("A".to_string());
+    commands.add_with_options("B".to_string(), parent("1"));
+    commands.add_with_options("C".to_string(), parent("2"));
+
+    commands.update_with_options(1, None, parent("3"));
+    commands.update_with_options(1, None, parent("1"));
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks[0].parent, None);
+
+    commands.update_with_options(3, None, parent("none"));
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks[2].parent, None);
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_delete_parent_removes_children() {
+    let test_file = "test_subtasks_delete_remove.json";
+    let storage = Storage::new(test_file);
+    storage.save_tasks(&[
+        Task::new(1, "Root".to_string()),
+        child(2, 1),
+        child(3, 2),
+        Task::new(4, "Unrelated".to_string()),
+    ]).unwrap();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.delete_with_children(1, Some(ChildAction::Remove));
+
+    let tasks = storage.load_tasks();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].id, 4);
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_delete_parent_reparents_children() {
+    let test_file = "test_subtasks_delete_reparent.json";
+    let storage = Storage::new(test_file);
+    storage.save_tasks(&[
+        Task::new(1, "Root".to_string()),
+        child(2, 1),
+        child(3, 2),
+        child(4, 2),
+    ]).unwrap();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.delete_with_children(2, Some(ChildAction::Reparent));
+
+    let tasks = storage.load_tasks();
+    assert_eq!(tasks.len(), 3);
+    assert_eq!(tasks[1].id, 3);
+    assert_eq!(tasks[1].parent, Some(1));
+    assert_eq!(tasks[2].parent, Some(1));
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_mark_parent_done_with_open_children_still_marks() {
+    let test_file = "test_subtasks_mark.json";
+    let storage = Storage::new(test_file);
+    storage.save_tasks(&[Task::new(1, "Root".to_string()), child(2, 1)]).unwrap();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.mark(1, "done".to_string());
+
+    let tasks = storage.load_tasks();
+    assert_eq!(tasks[0].status, TaskStatus::Done);
+    assert_eq!(tasks[1].status, TaskStatus::Todo);
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_list_as_tree() {
+    let test_file = "test_subtasks_tree.json";
+    let storage = Storage::new(test_file);
+    storage.save_tasks(&[Task::new(1, "Root".to_string()), child(2, 1), child(3, 2)]).unwrap();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.list_with_options(ListOptions { tree: true, ..ListOptions::default() });
+    commands.list_with_options(ListOptions {
+        tree: true,
+        group_by_project: true,
+        ..ListOptions::default()
+    });
+    commands.show(2);
+
+    fs::remove_file(test_file).ok();
+}
@@ -0,0 +1,103 @@
+use trackr::commands::{Commands, ListOptions, TaskOptions};
+use trackr::storage::Storage;
+use trackr::task::{tag_counts, Task};
+use std::fs;
+
+#[test]
+fn test_add_and_remove_tags() {
+    let mut task = Task::new(1, "Tagged".to_string());
+
+    assert!(task.add_tag("backend"));
+    assert!(!task.add_tag("backend"));
+    assert!(task.add_tag("urgent"));
+    assert_eq!(task.tags, vec!["backend", "urgent"]);
+
+    assert!(task.remove_tag("backend"));
+    assert!(!task.remove_tag("backend"));
+    assert!(task.has_tag("urgent"));
+    assert!(!task.has_tag("backend"));
+}
+
+#[test]
+fn test_valid_tags() {
+    assert!(Task::is_valid_tag("backend"));
+    assert!(Task::is_valid_tag("sprint-12"));
+    assert!(!Task::is_valid_tag(""));
+    assert!(!Task::is_valid_tag("two words"));
+    assert!(!Task::is_valid_tag("a,b"));
+}
+
+#[test]
+fn test_tags_round_trip_as_array() {
+    let test_file = "test_tags_round_trip.json";
+    let storage = Storage::new(test_file);
+
+    let mut task = Task::new(1, "Tagged".to_string());
+    task.add_tag("backend");
+    task.add_tag("urgent");
+    storage.save_tasks(&[task, Task::new(2, "Plain".to_string())]).unwrap();
+
+    let contents = fs::read_to_string(test_file).unwrap();
+    assert!(contents.contains("\"tags\": [\"backend\", \"urgent\"]"));
+    assert!(contents.contains("\"tags\": []"));
+
+    let loaded = storage.load_tasks();
+    assert_eq!(loaded[0].tags, vec!["backend", "urgent"]);
+    assert!(loaded[1].tags.is_empty());
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_tag_command_adds_and_removes() {
+    let test_file = "test_tags_command.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.add_with_options("Fix login".to_string(), TaskOptions {
+        tags: vec!["backend".to_string()],
+        ..TaskOptions::default()
+    });
+
+    commands.tag(1, vec!["+urgent".to_string(), "-backend".to_string(), "+api".to_string()]);
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks[0].tags, vec!["urgent", "api"]);
+
+    commands.tag(1, vec!["+bad tag".to_string(), "-urgent".to_string()]);
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks[0].tags, vec!["urgent", "api"]);
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_tag_counts() {
+    let mut a = Task::new(1, "A".to_string());
+    a.add_tag("backend");
+    a.add_tag("urgent");
+    let mut b = Task::new(2, "B".to_string());
+    b.add_tag("backend");
+    let c = Task::new(3, "C".to_string());
+
+    let counts = tag_counts(&[a, b, c]);
+    let counts: Vec<(&str, usize)> = counts.iter().map(|(tag, n)| (tag.as_str(), *n)).collect();
+    assert_eq!(counts, vec![("backend", 2), ("urgent", 1)]);
+}
+
+#[test]
+fn test_list_and_tags_commands() {
+    let test_file = "test_tags_list.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.tags();
+    commands.add("One".to_string());
+    commands.tag(1, vec!["+backend".to_string()]);
+    commands.tags();
+    commands.list_with_options(ListOptions {
+        tags: vec!["backend".to_string()],
+        ..ListOptions::default()
+    });
+
+    fs::remove_file(test_file).ok();
+}
@@ -0,0 +1,113 @@
+use trackr::commands::Commands;
+use trackr::datetime::{self, format_duration};
+use trackr::storage::Storage;
+use trackr::task::{Task, TaskStatus, WorkInterval};
+use std::fs;
+
+#[test]
+fn test_format_duration() {
+    assert_eq!(format_duration(0), "0s");
+    assert_eq!(format_duration(59), "59s");
+    assert_eq!(format_duration(60 * 12), "12m");
+    assert_eq!(format_duration(3600 * 2 + 60 * 5 + 30), "2h 05m");
+    assert_eq!(format_duration(3600 * 30), "30h 00m");
+}
+
+#[test]
+fn test_timer_lifecycle_on_task() {
+    let mut task = Task::new(1, "Write report".to_string());
+    assert!(!task.is_tracking());
+    assert_eq!(task.stop_timer(100), None);
+
+    assert!(task.start_timer(1_000));
+    assert!(!task.start_timer(1_010));
+    assert!(task.is_tracking());
+    assert_eq!(task.time_spent(1_090), 90);
+
+    assert_eq!(task.stop_timer(1_300), Some(300));
+    assert!(!task.is_tracking());
+    assert_eq!(task.time_spent(9_999), 300);
+
+    task.start_timer(2_000);
+    task.stop_timer(2_060);
+    assert_eq!(task.time_spent(9_999), 360);
+    assert_eq!(task.time_log[1], WorkInterval { start: 2_000, end: Some(2_060) });
+}
+
+#[test]
+fn test_start_and_stop_commands() {
+    let test_file = "test_time_tracking_commands.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.add("First".to_string());
+    commands.add("Second".to_string());
+
+    commands.start(1);
+    let tasks = Storage::new(test_file).load_tasks();
+    assert!(tasks[0].is_tracking());
+    assert_eq!(tasks[0].status, TaskStatus::InProgress);
+
+    // Starting another task stops the first timer.
+    commands.start(2);
+    let tasks = Storage::new(test_file).load_tasks();
+    assert!(!tasks[0].is_tracking());
+    assert_eq!(tasks[0].time_log.len(), 1);
+    assert!(tasks[1].is_tracking());
+    assert_eq!(tasks[1].status, TaskStatus::InProgress);
+
+    commands.stop(Some(1));
+    assert!(Storage::new(test_file).load_tasks()[1].is_tracking());
+
+    commands.stop(None);
+    let tasks = Storage::new(test_file).load_tasks();
+    assert!(tasks.iter().all(|t| !t.is_tracking()));
+    assert_eq!(tasks[1].time_log.len(), 1);
+
+    commands.start(99);
+    assert_eq!(Storage::new(test_file).load_tasks().len(), 2);
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_running_timer_survives_reload() {
+    let test_file = "test_time_tracking_persist.json";
+    fs::remove_file(test_file).ok();
+
+    let mut task = Task::new(1, "Long haul".to_string());
+    let now = datetime::now();
+    task.time_log = vec![
+        WorkInterval { start: now - 7_200, end: Some(now - 3_600) },
+        WorkInterval { start: now - 60, end: None },
+    ];
+    Storage::new(test_file).save_tasks(&[task]).unwrap();
+
+    let contents = fs::read_to_string(test_file).unwrap();
+    assert!(contents.contains("\"end\": null"));
+
+    let loaded = Storage::new(test_file).load_tasks();
+    assert_eq!(loaded[0].time_log.len(), 2);
+    assert!(loaded[0].is_tracking());
+    assert!(loaded[0].time_spent(now) >= 3_660);
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_marking_done_stops_timer() {
+    let test_file = "test_time_tracking_mark.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.add("Finish me".to_string());
+    commands.start(1);
+    commands.mark(1, "done".to_string());
+
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks[0].status, TaskStatus::Done);
+    assert!(!tasks[0].is_tracking());
+    assert_eq!(tasks[0].time_log.len(), 1);
+
+    fs::remove_file(test_file).ok();
+}
@@ -0,0 +1,123 @@
+use trackr::commands::Commands;
+use trackr::datetime;
+use trackr::storage::Storage;
+use trackr::task::{Task, TaskStatus};
+use std::fs::{self, File};
+use std::io::Write;
+
+#[test]
+fn test_format_and_parse_timestamp() {
+    assert_eq!(datetime::format_timestamp(0), "1970-01-01T00:00:00Z");
+    assert_eq!(datetime::format_timestamp(1_700_000_000), "2023-11-14T22:13:20Z");
+    assert_eq!(datetime::parse_timestamp("2023-11-14T22:13:20Z"), Some(1_700_000_000));
+    assert_eq!(datetime::parse_timestamp("2024-02-29T12:00:00Z"),
+        Some(datetime::parse_timestamp("2024-02-28T12:00:00Z").unwrap() + 86_400));
+    assert_eq!(datetime::parse_timestamp("not a time"), None);
+    assert_eq!(datetime::parse_timestamp("2023-13-01T00:00:00Z"), None);
+}
+
+#[test]
+fn test_format_age() {
+    assert_eq!(datetime::format_age(30), "now");
+    assert_eq!(datetime::format_age(5 * 60), "5m");
+    assert_eq!(datetime::format_age(3 * 3600), "3h");
+    assert_eq!(datetime::format_age(4 * 86_400 + 10), "4d");
+}
+
+#[test]
+fn test_format_ago() {
+    assert_eq!(datetime::format_ago(30), "just now");
+    assert_eq!(datetime::format_ago(5 * 60), "5m ago");
+    assert_eq!(datetime::format_ago(4 * 86_400), "4d ago");
+}
+
+#[test]
+fn test_set_status_tracks_completion() {
+    let mut task = Task::new(1, "Ship it".to_string());
+
+    task.set_status(TaskStatus::Done, 100);
+    assert_eq!(task.completed_at, Some(100));
+    assert_eq!(task.updated_at, Some(100));
+
+    task.set_status(TaskStatus::Done, 200);
+    assert_eq!(task.completed_at, Some(100));
+    assert_eq!(task.updated_at, Some(200));
+
+    task.set_status(TaskStatus::Todo, 300);
+    assert_eq!(task.completed_at, None);
+    assert_eq!(task.updated_at, Some(300));
+}
+
+#[test]
+fn test_commands_keep_timestamps_current() {
+    let test_file = "test_timestamps_commands.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.add("Timestamped".to_string());
+
+    let tasks = Storage::new(test_file).load_tasks();
+    assert!(tasks[0].created_at.is_some());
+    assert_eq!(tasks[0].created_at, tasks[0].updated_at);
+    assert_eq!(tasks[0].completed_at, None);
+
+    commands.mark(1, "done".to_string());
+    let tasks = Storage::new(test_file).load_tasks();
+    assert!(tasks[0].completed_at.is_some());
+
+    commands.update(1, "Renamed".to_string());
+    commands.mark(1, "in-progress".to_string());
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks[0].completed_at, None);
+    assert!(tasks[0].updated_at >= tasks[0].created_at);
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_timestamps_round_trip() {
+    let test_file = "test_timestamps_round_trip.json";
+    let storage = Storage::new(test_file);
+
+    let mut task = Task::new(1, "Round trip".to_string());
+    task.created_at = Some(1_700_000_000);
+    task.set_status(TaskStatus::Done, 1_700_086_400);
+    storage.save_tasks(&[task]).unwrap();
+
+    let contents = fs::read_to_string(test_file).unwrap();
+    assert!(contents.contains("\"created_at\": \"2023-11-14T22:13:20Z\""));
+
+    let loaded = storage.load_tasks();
+    assert_eq!(loaded[0].created_at, Some(1_700_000_000));
+    assert_eq!(loaded[0].updated_at, Some(1_700_086_400));
+    assert_eq!(loaded[0].completed_at, Some(1_700_086_400));
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_legacy_file_without_timestamps_loads() {
+    let test_file = "test_timestamps_legacy.json";
+
+    let json = r#"[
+  {
+    "id": 1,
+    "description": "Old task",
+    "status": "done"
+  }
+]"#;
+
+    let mut file = File::create(test_file).unwrap();
+    file.write_all(json.as_bytes()).unwrap();
+    drop(file);
+
+    let tasks = Storage::new(test_file).load_tasks();
+
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].status, TaskStatus::Done);
+    assert_eq!(tasks[0].created_at, None);
+    assert_eq!(tasks[0].updated_at, None);
+    assert_eq!(tasks[0].completed_at, None);
+
+    fs::remove_file(test_file).ok();
+}
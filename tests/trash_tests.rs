@@ -0,0 +1,125 @@
+use trackr::commands::{ChildAction, Commands};
+use trackr::datetime::{self, parse_duration};
+use trackr::storage::Storage;
+use trackr::task::{open_blockers, Task};
+use std::fs;
+
+#[test]
+fn test_parse_duration() {
+    assert_eq!(parse_duration("30d"), Some(30 * 86400));
+    assert_eq!(parse_duration("2w"), Some(14 * 86400));
+    assert_eq!(parse_duration("12h"), Some(12 * 3600));
+    assert_eq!(parse_duration("30"), None);
+    assert_eq!(parse_duration("d"), None);
+    assert_eq!(parse_duration("-1d"), None);
+}
+
+#[test]
+fn test_delete_moves_to_trash_and_restore() {
+    let test_file = "test_trash_restore.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.add("Keep".to_string());
+    commands.add("Oops".to_string());
+    commands.depend(1, 2);
+    commands.delete(2);
+
+    let storage = Storage::new(test_file);
+    let tasks = storage.load_tasks();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].depends_on, vec![2]);
+    assert!(open_blockers(&tasks, &tasks[0]).is_empty());
+    let trash = storage.load_trash();
+    assert_eq!(trash.len(), 1);
+    assert_eq!(trash[0].description, "Oops");
+    assert!(trash[0].deleted_at.is_some());
+
+    commands.restore(2);
+    let tasks = storage.load_tasks();
+    assert_eq!(tasks.iter().map(|t| t.id).collect::<Vec<_>>(), vec![1, 2]);
+    assert_eq!(tasks[1].deleted_at, None);
+    assert_eq!(tasks[0].depends_on, vec![2]);
+    assert!(storage.load_trash().is_empty());
+
+    commands.restore(2);
+    assert_eq!(storage.load_tasks().len(), 2);
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_restore_brings_back_deleted_subtasks() {
+    let test_file = "test_trash_subtasks.json";
+    fs::remove_file(test_file).ok();
+
+    let storage = Storage::new(test_file);
+    let mut child = Task::new(2, "Child".to_string());
+    child.parent = Some(1);
+    let mut orphan = Task::new(3, "Orphan".to_string());
+    orphan.parent = Some(99);
+    storage.save_tasks(&[Task::new(1, "Parent".to_string()), child, orphan]).unwrap();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.delete_with_children(1, Some(ChildAction::Remove));
+    assert_eq!(storage.load_trash().len(), 2);
+
+    commands.restore(1);
+    let tasks = storage.load_tasks();
+    assert_eq!(tasks.iter().map(|t| t.id).collect::<Vec<_>>(), vec![1, 2, 3]);
+    assert_eq!(tasks[1].parent, Some(1));
+    assert_eq!(tasks[2].parent, Some(99));
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_restore_clears_missing_parent() {
+    let test_file = "test_trash_missing_parent.json";
+    fs::remove_file(test_file).ok();
+
+    let storage = Storage::new(test_file);
+    let mut child = Task::new(2, "Child".to_string());
+    child.parent = Some(1);
+    child.deleted_at = Some(datetime::now());
+    storage.save_with_trash(&[], &[child]).unwrap();
+
+    Commands::new(Storage::new(test_file)).restore(2);
+    assert_eq!(storage.load_tasks()[0].parent, None);
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_empty_trash() {
+    let test_file = "test_trash_empty.json";
+    fs::remove_file(test_file).ok();
+
+    let now = datetime::now();
+    let trashed = |id: u32, days_ago: u64| {
+        let mut task = Task::new(id, format!("Task {}", id));
+        task.deleted_at = Some(now - days_ago * 86400);
+        task
+    };
+    let mut alive = Task::new(4, "Alive".to_string());
+    alive.depends_on = vec![1, 2];
+    let storage = Storage::new(test_file);
+    storage.save_with_trash(&[alive], &[trashed(1, 40), trashed(2, 5), trashed(3, 31)]).unwrap();
+
+    // Dependencies on purged tasks go, the rest wait for a restore.
+    let commands = Commands::new(Storage::new(test_file));
+    commands.empty_trash(parse_duration("30d"));
+    assert_eq!(storage.load_trash().iter().map(|t| t.id).collect::<Vec<_>>(), vec![2]);
+    assert_eq!(storage.load_tasks()[0].depends_on, vec![2]);
+
+    commands.empty_trash(None);
+    assert!(storage.load_trash().is_empty());
+    assert_eq!(storage.load_tasks().len(), 1);
+    assert!(storage.load_tasks()[0].depends_on.is_empty());
+
+    // Purged ids stay taken.
+    commands.add("New".to_string());
+    assert_eq!(storage.load_tasks()[1].id, 5);
+
+    fs::remove_file(test_file).ok();
+}
@@ -0,0 +1,130 @@
+use trackr::commands::{Commands, ListOptions, TaskOptions};
+use trackr::config::{Config, UdaDef, UdaType};
+use trackr::datetime::Date;
+use trackr::storage::Storage;
+use trackr::task::UdaValue;
+use std::cmp::Ordering;
+use std::fs;
+
+const CONFIG: &str = "\
+[uda points]
+type = number
+
+[uda ticket]
+
+[uda review]
+type = date
+
+[uda severity]
+type = enum
+values = low, medium, high
+";
+
+fn storage(path: &str) -> Storage {
+    let config = Config::parse(CONFIG).unwrap();
+    Storage::with_workflow(path, config.workflow).with_udas(config.udas)
+}
+
+fn set(assignments: &[&str]) -> TaskOptions {
+    TaskOptions {
+        set: assignments.iter().map(|a| a.to_string()).collect(),
+        ..TaskOptions::default()
+    }
+}
+
+#[test]
+fn test_parse_uda_config() {
+    let config = Config::parse(CONFIG).unwrap();
+    assert_eq!(config.udas.len(), 4);
+    assert_eq!(config.udas[0], UdaDef { name: "points".to_string(), kind: UdaType::Number });
+    assert_eq!(config.udas[1].kind, UdaType::String);
+    assert_eq!(config.udas[2].kind, UdaType::Date);
+    assert_eq!(config.udas[3].kind, UdaType::Enum(vec![
+        "low".to_string(), "medium".to_string(), "high".to_string(),
+    ]));
+}
+
+#[test]
+fn test_parse_uda_config_errors() {
+    assert!(Config::parse("[uda points]\ntype = integer\n").is_err());
+    assert!(Config::parse("[uda severity]\ntype = enum\n").is_err());
+    assert!(Config::parse("[uda priority]\ntype = number\n").is_err());
+    assert!(Config::parse("[uda urgency]\ntype = number\n").is_err());
+    assert!(Config::parse("[uda has space]\n").is_err());
+    assert!(Config::parse("[uda points]\nlabel = Points\n").is_err());
+}
+
+#[test]
+fn test_parse_and_compare_values() {
+    let config = Config::parse(CONFIG).unwrap();
+    let (points, review, severity) = (&config.udas[0], &config.udas[2], &config.udas[3]);
+
+    assert_eq!(points.parse_value("2.5"), Ok(UdaValue::Number(2.5)));
+    assert!(points.parse_value("lots").is_err());
+    assert_eq!(review.parse_value("2024-05-01"), Ok(UdaValue::Date(Date::parse("2024-05-01").unwrap())));
+    assert!(review.parse_value("soon").is_err());
+    assert_eq!(severity.parse_value("HIGH"), Ok(UdaValue::String("high".to_string())));
+    assert!(severity.parse_value("urgent").is_err());
+
+    let low = UdaValue::String("low".to_string());
+    let high = UdaValue::String("high".to_string());
+    assert_eq!(severity.compare(&low, &high), Ordering::Less);
+    assert_eq!(points.compare(&UdaValue::Number(8.0), &UdaValue::Number(13.0)), Ordering::Less);
+}
+
+#[test]
+fn test_uda_round_trip() {
+    let test_file = "test_uda_round_trip.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(storage(test_file));
+    commands.add_with_options("Fix login".to_string(),
+        set(&["points=3", "ticket=https://example.com/ABC-12", "review=2024-05-01", "severity=High"]));
+    commands.add_with_options("Bad points".to_string(), set(&["points=lots"]));
+    commands.add_with_options("Unknown attribute".to_string(), set(&["customer=Acme"]));
+
+    let tasks = storage(test_file).load_tasks();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].udas.get("points"), Some(&UdaValue::Number(3.0)));
+    assert_eq!(tasks[0].udas.get("ticket"), Some(&UdaValue::String("https://example.com/ABC-12".to_string())));
+    assert_eq!(tasks[0].udas.get("review"), Some(&UdaValue::Date(Date::parse("2024-05-01").unwrap())));
+    assert_eq!(tasks[0].udas.get("severity"), Some(&UdaValue::String("high".to_string())));
+
+    let contents = fs::read_to_string(test_file).unwrap();
+    assert!(contents.contains("\"points\": 3,"));
+    assert!(contents.contains("\"severity\": \"high\""));
+
+    commands.update_with_options(1, None, set(&["points=5", "ticket=none"]));
+    let tasks = storage(test_file).load_tasks();
+    assert_eq!(tasks[0].udas.get("points"), Some(&UdaValue::Number(5.0)));
+    assert_eq!(tasks[0].udas.get("ticket"), None);
+
+    // Without the declarations the values still load, as they were written.
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks[0].udas.get("points"), Some(&UdaValue::Number(5.0)));
+    assert_eq!(tasks[0].udas.get("severity"), Some(&UdaValue::String("high".to_string())));
+
+    commands.show(1);
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_list_filter_and_sort_by_uda() {
+    let test_file = "test_uda_list.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(storage(test_file));
+    commands.add_with_options("Big".to_string(), set(&["points=8", "severity=low"]));
+    commands.add_with_options("Small".to_string(), set(&["points=1", "severity=high"]));
+    commands.add("Unestimated".to_string());
+
+    commands.list_with_options(ListOptions { sort: Some("points".to_string()), ..ListOptions::default() });
+    commands.list_with_options(ListOptions { sort: Some("severity".to_string()), ..ListOptions::default() });
+    commands.list_with_options(ListOptions { wheres: vec!["severity=high".to_string()], ..ListOptions::default() });
+    commands.list_with_options(ListOptions { wheres: vec!["points=none".to_string()], ..ListOptions::default() });
+    commands.list_with_options(ListOptions { wheres: vec!["points=many".to_string()], ..ListOptions::default() });
+    commands.list_with_options(ListOptions { sort: Some("customer".to_string()), ..ListOptions::default() });
+
+    fs::remove_file(test_file).ok();
+}
@@ -0,0 +1,100 @@
+use trackr::commands::{Commands, ListOptions, TaskOptions};
+use trackr::config::{Config, Urgency, Workflow};
+use trackr::datetime::{self, Date};
+use trackr::storage::Storage;
+use trackr::task::{Priority, Task, TaskStatus};
+use std::fs;
+
+const DAY: u64 = 86_400;
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9
+}
+
+#[test]
+fn test_score_components() {
+    let urgency = Urgency::default();
+    let today = Date::parse("2024-06-15").unwrap();
+    let now = 1_000 * DAY;
+
+    let plain = Task::new(1, "Plain".to_string());
+    assert!(close(urgency.score(&plain, &[], today, now), 0.0));
+
+    let mut task = Task::new(2, "Busy".to_string());
+    task.status = TaskStatus::InProgress;
+    task.priority = Some(Priority::High);
+    assert!(close(urgency.score(&task, &[], today, now), 4.0 + 6.0));
+
+    let mut due = Task::new(3, "Due".to_string());
+    due.due = Some(today.add_days(-7));
+    assert!(close(urgency.score(&due, &[], today, now), 12.0));
+    due.due = Some(today.add_days(30));
+    assert!(close(urgency.score(&due, &[], today, now), 12.0 * 0.2));
+    due.due = Some(today);
+    assert!(close(urgency.score(&due, &[], today, now), 12.0 * (14.0 * 0.8 / 21.0 + 0.2)));
+
+    let mut old = Task::new(4, "Old".to_string());
+    old.created_at = Some(now - 730 * DAY);
+    assert!(close(urgency.score(&old, &[], today, now), 2.0));
+
+    let mut done = Task::new(5, "Done".to_string());
+    done.priority = Some(Priority::Critical);
+    done.status = TaskStatus::Done;
+    assert!(close(urgency.score(&done, &[], today, now), 0.0));
+}
+
+#[test]
+fn test_score_blocking_and_blocked() {
+    let urgency = Urgency::default();
+    let today = Date::parse("2024-06-15").unwrap();
+
+    let blocker = Task::new(1, "Blocker".to_string());
+    let mut blocked = Task::new(2, "Blocked".to_string());
+    blocked.depends_on = vec![1];
+    let tasks = vec![blocker.clone(), blocked.clone()];
+
+    assert!(close(urgency.score(&blocker, &tasks, today, 0), 8.0));
+    assert!(close(urgency.score(&blocked, &tasks, today, 0), -5.0));
+}
+
+#[test]
+fn test_urgency_config() {
+    let config = Config::parse("[urgency]\ndue = 20\nblocked = -1.5\n").unwrap();
+    assert!(close(config.urgency.due, 20.0));
+    assert!(close(config.urgency.blocked, -1.5));
+    assert!(close(config.urgency.age, Urgency::default().age));
+
+    assert!(Config::parse("[urgency]\nurgent = 1\n").is_err());
+    assert!(Config::parse("[urgency]\ndue = lots\n").is_err());
+}
+
+#[test]
+fn test_next_and_sort_by_urgency() {
+    let test_file = "test_urgency_next.json";
+    fs::remove_file(test_file).ok();
+
+    let high = || TaskOptions { priority: Some("critical".to_string()), ..TaskOptions::default() };
+    let workflow = Workflow::parse("[status blocked]\n\n[transitions]\ntodo = in-progress, blocked\nblocked = todo\n").unwrap();
+    let commands = Commands::new(Storage::with_workflow(test_file, workflow));
+    let next_id = || {
+        let tasks = Storage::new(test_file).load_tasks();
+        commands.next_task(&tasks, Date::today(), datetime::now()).map(|t| t.id)
+    };
+    commands.next();
+    assert_eq!(next_id(), None);
+
+    commands.add("Low key".to_string());
+    commands.add_with_options("Urgent but blocked".to_string(), high());
+    commands.add("Blocker".to_string());
+    commands.depend(2, 3);
+    commands.add_with_options("Waiting on someone".to_string(), high());
+    commands.mark(4, "blocked".to_string());
+
+    // #3 holds up #2, and #4's status can't move straight to in-progress.
+    assert_eq!(next_id(), Some(3));
+    commands.next();
+    commands.list_with_options(ListOptions { sort: Some("urgency".to_string()), ..ListOptions::default() });
+    commands.show(3);
+
+    fs::remove_file(test_file).ok();
+}
@@ -0,0 +1,122 @@
+use trackr::commands::{Commands, TaskOptions};
+use trackr::storage::Storage;
+use trackr::task::{derived_uuid, new_uuid, resolve_id, Task, TaskStatus};
+use std::fs::{self, File};
+use std::io::Write;
+
+fn with_uuid(id: u32, uuid: &str) -> Task {
+    let mut task = Task::new(id, format!("Task {}", id));
+    task.uuid = uuid.to_string();
+    task
+}
+
+#[test]
+fn test_new_uuid_format() {
+    let uuid = new_uuid();
+    let groups: Vec<&str> = uuid.split('-').collect();
+
+    assert_eq!(groups.iter().map(|g| g.len()).collect::<Vec<_>>(), vec![8, 4, 4, 4, 12]);
+    assert!(uuid.chars().all(|c| c == '-' || c.is_ascii_hexdigit() && !c.is_ascii_uppercase()));
+    assert!(groups[2].starts_with('4'));
+    assert!(matches!(groups[3].chars().next(), Some('8' | '9' | 'a' | 'b')));
+    assert_ne!(new_uuid(), uuid);
+    assert_ne!(Task::new(1, "A".to_string()).uuid, Task::new(1, "A".to_string()).uuid);
+
+    let derived = derived_uuid("1\nOld task\n");
+    assert_eq!(derived, derived_uuid("1\nOld task\n"));
+    assert_ne!(derived, derived_uuid("2\nOld task\n"));
+    assert_eq!(derived.len(), 36);
+    assert_eq!(&derived[14..15], "8");
+}
+
+#[test]
+fn test_resolve_id() {
+    let tasks = vec![
+        with_uuid(1, "3f2a9c1e-0000-4000-8000-000000000001"),
+        with_uuid(2, "3f2b0000-0000-4000-8000-000000000002"),
+        with_uuid(3, "12345678-0000-4000-8000-000000000003"),
+    ];
+
+    assert_eq!(resolve_id(&tasks, "2"), Ok(2));
+    assert_eq!(resolve_id(&tasks, "3f2a"), Ok(1));
+    assert_eq!(resolve_id(&tasks, "3F2B"), Ok(2));
+    assert_eq!(resolve_id(&tasks, "123456"), Ok(3));
+    // Short numbers are always ids, even when they also prefix a UUID.
+    assert_eq!(resolve_id(&tasks, "1234"), Ok(1234));
+    assert!(resolve_id(&tasks, "3f2").unwrap_err().contains("#1, #2"));
+    assert!(resolve_id(&tasks, "ffff").is_err());
+    assert!(resolve_id(&tasks, "not-hex").is_err());
+    assert!(resolve_id(&tasks, "").is_err());
+
+    // Unknown numbers pass through so commands can say the task is missing.
+    assert_eq!(resolve_id(&tasks, "99"), Ok(99));
+}
+
+#[test]
+fn test_uuid_persists_across_saves() {
+    let test_file = "test_uuid_persist.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.add("Stable".to_string());
+    let uuid = Storage::new(test_file).load_tasks()[0].uuid.clone();
+
+    commands.mark(1, "done".to_string());
+    commands.update(1, "Still stable".to_string());
+    assert_eq!(Storage::new(test_file).load_tasks()[0].uuid, uuid);
+
+    let contents = fs::read_to_string(test_file).unwrap();
+    assert!(contents.contains(&format!("\"uuid\": \"{}\"", uuid)));
+
+    let id = commands.resolve_id(&uuid[..6]).unwrap();
+    assert_eq!(id, 1);
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_legacy_file_gets_uuids() {
+    let test_file = "test_uuid_legacy.json";
+    let json = r#"[
+  {
+    "id": 1,
+    "description": "Old task",
+    "status": "todo"
+  }
+]"#;
+    let mut file = File::create(test_file).unwrap();
+    file.write_all(json.as_bytes()).unwrap();
+    drop(file);
+
+    // The UUID is the same on every load, so one shown before the first save
+    // still finds the task afterwards.
+    let uuid = Storage::new(test_file).load_tasks()[0].uuid.clone();
+    assert_eq!(Storage::new(test_file).load_tasks()[0].uuid, uuid);
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.mark(commands.resolve_id(&uuid[..8]).unwrap(), "in-progress".to_string());
+
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks[0].status, TaskStatus::InProgress);
+    assert_eq!(tasks[0].uuid, uuid);
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_parent_by_uuid_prefix() {
+    let test_file = "test_uuid_parent.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.add("Parent".to_string());
+    let uuid = Storage::new(test_file).load_tasks()[0].uuid.clone();
+
+    let options = TaskOptions { parent: Some(uuid[..8].to_string()), ..TaskOptions::default() };
+    commands.add_with_options("Child".to_string(), options);
+
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks[1].parent, Some(1));
+
+    fs::remove_file(test_file).ok();
+}
@@ -0,0 +1,70 @@
+use trackr::commands::{Commands, ListOptions, TaskOptions};
+use trackr::datetime::Date;
+use trackr::storage::Storage;
+use trackr::task::Task;
+use std::fs;
+
+fn wait(date: &str) -> TaskOptions {
+    TaskOptions { wait: Some(date.to_string()), ..TaskOptions::default() }
+}
+
+#[test]
+fn test_is_waiting() {
+    let today = Date::parse("2024-06-15").unwrap();
+    let mut task = Task::new(1, "Revisit".to_string());
+    assert!(!task.is_waiting(today));
+
+    task.wait = Date::parse("2024-06-16");
+    assert!(task.is_waiting(today));
+
+    task.wait = Some(today);
+    assert!(!task.is_waiting(today));
+
+    task.wait = Date::parse("2024-01-01");
+    assert!(!task.is_waiting(today));
+}
+
+#[test]
+fn test_wait_from_add_and_update() {
+    let test_file = "test_wait_options.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.add_with_options("Next quarter".to_string(), wait("2099-01-01"));
+    commands.add_with_options("Bad date".to_string(), wait("someday"));
+    commands.add("Now".to_string());
+
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks.len(), 2);
+    assert_eq!(tasks[0].wait, Date::parse("2099-01-01"));
+    assert!(tasks[0].is_waiting(Date::today()));
+    assert_eq!(tasks[1].wait, None);
+
+    let contents = fs::read_to_string(test_file).unwrap();
+    assert!(contents.contains("\"wait\": \"2099-01-01\""));
+    assert!(contents.contains("\"wait\": null"));
+
+    commands.update_with_options(2, None, wait("2030-03-01"));
+    commands.update_with_options(1, None, wait("none"));
+    let tasks = Storage::new(test_file).load_tasks();
+    assert_eq!(tasks[0].wait, None);
+    assert_eq!(tasks[1].wait, Date::parse("2030-03-01"));
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_list_with_waiting_tasks() {
+    let test_file = "test_wait_list.json";
+    fs::remove_file(test_file).ok();
+
+    let commands = Commands::new(Storage::new(test_file));
+    commands.add_with_options("Hidden".to_string(), wait("2099-01-01"));
+
+    commands.list(None);
+    commands.list_with_options(ListOptions { all: true, ..ListOptions::default() });
+
+    assert_eq!(Storage::new(test_file).load_tasks().len(), 1);
+
+    fs::remove_file(test_file).ok();
+}
@@ -0,0 +1,163 @@
+use trackr::commands::Commands;
+use trackr::config::{Config, Workflow};
+use trackr::storage::Storage;
+use trackr::task::{is_ready, Task, TaskStatus};
+use std::fs;
+
+const CONFIG: &str = "
+# our team process
+[status blocked]
+emoji = 🧱
+color = red
+
+[status cancelled]
+emoji = 🚫
+color = gray
+closed = true
+
+[status in-review]
+color = 96
+
+[transitions]
+todo = in-progress, blocked, cancelled
+in-progress = in-review, blocked
+in-review = done, in-progress
+";
+
+fn cancelled() -> TaskStatus {
+    TaskStatus::Custom { name: "cancelled".to_string(), closed: true }
+}
+
+#[test]
+fn test_default_workflow_matches_builtins() {
+    let workflow = Workflow::default();
+
+    assert_eq!(workflow.names(), vec!["todo", "in-progress", "done"]);
+    assert_eq!(workflow.status("Done"), Some(TaskStatus::Done));
+    assert_eq!(workflow.status("blocked"), None);
+    assert_eq!(workflow.emoji(&TaskStatus::Todo), "📝");
+    assert_eq!(workflow.color(&TaskStatus::InProgress), "\x1b[93m");
+    assert!(workflow.can_transition(&TaskStatus::Done, &TaskStatus::Todo));
+}
+
+#[test]
+fn test_parse_workflow_config() {
+    let workflow = Workflow::parse(CONFIG).unwrap();
+
+    assert_eq!(workflow.names(), vec!["todo", "in-progress", "done", "blocked", "cancelled", "in-review"]);
+    assert_eq!(workflow.status("CANCELLED"), Some(cancelled()));
+    assert_eq!(workflow.emoji(&cancelled()), "🚫");
+    assert_eq!(workflow.color(&cancelled()), "\x1b[90m");
+    assert!(cancelled().is_closed());
+
+    let blocked = workflow.status("blocked").unwrap();
+    assert!(!blocked.is_closed());
+    assert_eq!(blocked.to_string(), "blocked");
+
+    assert!(workflow.can_transition(&TaskStatus::Todo, &blocked));
+    assert!(!workflow.can_transition(&TaskStatus::Todo, &TaskStatus::Done));
+    assert!(workflow.can_transition(&TaskStatus::Todo, &TaskStatus::Todo));
+    // No entry for blocked, so it may go anywhere.
+    assert!(workflow.can_transition(&blocked, &TaskStatus::Done));
+}
+
+#[test]
+fn test_parse_workflow_errors() {
+    let error = Workflow::parse("[status blocked]\ncolor = chartreuse\n").unwrap_err();
+    assert!(error.starts_with("line 2:"), "{}", error);
+
+    assert!(Workflow::parse("emoji = 🧱").is_err());
+    assert!(Workflow::parse("[status has space]").is_err());
+    assert!(Workflow::parse("[statuses]").is_err());
+    assert!(Workflow::parse("[status done]\nclosed = false").is_err());
+    assert!(Workflow::parse("[transitions]\ntodo = nowhere").is_err());
+    assert!(Workflow::parse("[status done]\nemoji = ✅").is_ok());
+}
+
+#[test]
+fn test_trailing_comments_are_ignored() {
+    let config = Config::parse("\
+[user]
+name = sam         # who you are for `list --mine`, defaults to $USER
+
+[status cancelled]  # gave up
+emoji = #️⃣
+closed = true      # counts as finished, like done
+").unwrap();
+
+    assert_eq!(config.user, Some("sam".to_string()));
+    assert_eq!(config.workflow.status("cancelled"), Some(cancelled()));
+    assert_eq!(config.workflow.emoji(&cancelled()), "#️⃣");
+}
+
+#[test]
+fn test_missing_config_uses_defaults() {
+    let config = Config::load("test_workflow_missing_config").unwrap();
+    assert_eq!(config.workflow.names().len(), 3);
+    assert_eq!(config.user, None);
+}
+
+#[test]
+fn test_closed_custom_status_counts_as_finished() {
+    let mut dep = Task::new(1, "Dep".to_string());
+    dep.set_status(cancelled(), 100);
+    assert_eq!(dep.completed_at, Some(100));
+
+    let mut task = Task::new(2, "Blocked".to_string());
+    task.depends_on = vec![1];
+    assert!(is_ready(&[dep.clone(), task.clone()], &task));
+}
+
+#[test]
+fn test_mark_follows_configured_transitions() {
+    let test_file = "test_workflow_mark.json";
+    fs::remove_file(test_file).ok();
+
+    let storage = || Storage::with_workflow(test_file, Workflow::parse(CONFIG).unwrap());
+    let commands = Commands::new(storage());
+    commands.add("Ship it".to_string());
+
+    commands.mark(1, "done".to_string());
+    assert_eq!(storage().load_tasks()[0].status, TaskStatus::Todo);
+
+    commands.mark(1, "in-progress".to_string());
+    commands.mark(1, "in-review".to_string());
+    let tasks = storage().load_tasks();
+    assert_eq!(tasks[0].status.to_string(), "in-review");
+
+    commands.mark(1, "done".to_string());
+    assert_eq!(storage().load_tasks()[0].status, TaskStatus::Done);
+
+    let contents = fs::read_to_string(test_file).unwrap();
+    assert!(contents.contains("\"status\": \"done\""));
+
+    fs::remove_file(test_file).ok();
+}
+
+#[test]
+fn test_custom_status_round_trip() {
+    let test_file = "test_workflow_round_trip.json";
+    fs::remove_file(test_file).ok();
+
+    let storage = Storage::with_workflow(test_file, Workflow::parse(CONFIG).unwrap());
+    let commands = Commands::new(Storage::with_workflow(test_file, Workflow::parse(CONFIG).unwrap()));
+    commands.add("Drop it".to_string());
+    commands.mark(1, "cancelled".to_string());
+
+    let tasks = storage.load_tasks();
+    assert_eq!(tasks[0].status, cancelled());
+    assert!(tasks[0].completed_at.is_some());
+
+    // Without the config the task keeps its status as an open custom one,
+    // and can still be moved out of it.
+    let storage = Storage::new(test_file);
+    assert_eq!(storage.check(), Ok(()));
+    assert_eq!(storage.recover(), Ok(None));
+    let tasks = storage.load_tasks();
+    assert_eq!(tasks[0].status, TaskStatus::Custom { name: "cancelled".to_string(), closed: false });
+
+    Commands::new(Storage::new(test_file)).mark(1, "todo".to_string());
+    assert_eq!(Storage::new(test_file).load_tasks()[0].status, TaskStatus::Todo);
+
+    fs::remove_file(test_file).ok();
+}